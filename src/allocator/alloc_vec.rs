@@ -0,0 +1,185 @@
+use crate::allocator::SharedAllocator;
+use alloc::alloc::Layout;
+use core::ops::{Deref, DerefMut};
+use core::ptr::NonNull;
+use core::{fmt, slice};
+
+/// Growable array of `Copy` values that draws memory from a
+/// [`SharedAllocator`].
+pub(crate) struct AllocVec<T> {
+    ptr: NonNull<T>,
+    len: usize,
+    cap: usize,
+    allocator: SharedAllocator,
+}
+
+impl<T> AllocVec<T>
+where
+    T: Copy,
+{
+    #[must_use]
+    pub const fn new() -> Self {
+        Self::new_in(SharedAllocator::Global)
+    }
+
+    #[must_use]
+    pub const fn new_in(allocator: SharedAllocator) -> Self {
+        assert!(
+            size_of::<T>() != 0,
+            "AllocVec does not support zero-sized types"
+        );
+
+        Self {
+            ptr: NonNull::dangling(),
+            len: 0,
+            cap: 0,
+            allocator,
+        }
+    }
+
+    pub fn push(&mut self, value: T) {
+        if self.len == self.cap {
+            self.reserve(1);
+        }
+
+        unsafe {
+            self.ptr.add(self.len).write(value);
+        }
+
+        self.len += 1;
+    }
+
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        assert!(index < self.len, "Index out of bounds");
+        self.len -= 1;
+
+        unsafe {
+            let value = self.ptr.add(index).read();
+            self.ptr.add(self.len).copy_to(self.ptr.add(index), 1);
+            value
+        }
+    }
+
+    pub fn resize(&mut self, new_len: usize, value: T) {
+        if new_len > self.len {
+            self.reserve(new_len - self.len);
+
+            for i in self.len..new_len {
+                unsafe {
+                    self.ptr.add(i).write(value);
+                }
+            }
+        }
+
+        self.len = new_len;
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        let min_cap = self.len.checked_add(additional).expect("Capacity overflow");
+
+        if min_cap <= self.cap {
+            return;
+        }
+
+        let new_cap = min_cap.max(self.cap.saturating_mul(2)).max(4);
+        let new_layout = Layout::array::<T>(new_cap).expect("Capacity overflow");
+        let new_ptr = self.allocator.allocate(new_layout).cast::<T>();
+
+        unsafe {
+            self.ptr.copy_to_nonoverlapping(new_ptr, self.len);
+            self.deallocate();
+        }
+
+        self.ptr = new_ptr;
+        self.cap = new_cap;
+    }
+
+    unsafe fn deallocate(&mut self) {
+        if self.cap != 0 {
+            let layout = Layout::array::<T>(self.cap).unwrap_unchecked();
+            self.allocator.deallocate(self.ptr.cast(), layout);
+        }
+    }
+}
+
+unsafe impl<T> Send for AllocVec<T>
+where
+    T: Send,
+{
+    // Empty
+}
+
+unsafe impl<T> Sync for AllocVec<T>
+where
+    T: Sync,
+{
+    // Empty
+}
+
+impl<T> Deref for AllocVec<T> {
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<T> DerefMut for AllocVec<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<T> Clone for AllocVec<T>
+where
+    T: Copy,
+{
+    fn clone(&self) -> Self {
+        let mut vec = Self::new_in(self.allocator.clone());
+        vec.reserve(self.len);
+
+        unsafe {
+            self.ptr.copy_to_nonoverlapping(vec.ptr, self.len);
+        }
+
+        vec.len = self.len;
+        vec
+    }
+}
+
+impl<T> Default for AllocVec<T>
+where
+    T: Copy,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for AllocVec<T> {
+    fn drop(&mut self) {
+        if self.cap != 0 {
+            unsafe {
+                let layout = Layout::array::<T>(self.cap).unwrap_unchecked();
+                self.allocator.deallocate(self.ptr.cast(), layout);
+            }
+        }
+    }
+}
+
+impl<T> fmt::Debug for AllocVec<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
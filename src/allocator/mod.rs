@@ -0,0 +1,159 @@
+//! Custom memory allocation for component and entity storages.
+
+mod alloc_vec;
+
+pub(crate) use self::alloc_vec::*;
+
+use alloc::alloc::{alloc, dealloc, handle_alloc_error, Layout};
+use alloc::sync::Arc;
+use core::fmt;
+use core::ptr::NonNull;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// Memory allocator used by the component and entity storages of a
+/// [`World`](crate::world::World).
+///
+/// # Safety
+///
+/// Memory blocks returned by [`allocate`](Self::allocate) must fit the
+/// requested layout and must remain valid until they are passed to
+/// [`deallocate`](Self::deallocate).
+pub unsafe trait Allocator: Send + Sync {
+    /// Allocates a memory block that fits `layout`, which is never zero-sized.
+    ///
+    /// Returns `None` if the allocation failed.
+    #[must_use]
+    fn allocate(&self, layout: Layout) -> Option<NonNull<u8>>;
+
+    /// Deallocates a memory block previously returned by
+    /// [`allocate`](Self::allocate) with the same `layout`.
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout);
+}
+
+/// The global memory allocator.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct Global;
+
+unsafe impl Allocator for Global {
+    #[inline]
+    fn allocate(&self, layout: Layout) -> Option<NonNull<u8>> {
+        NonNull::new(unsafe { alloc(layout) })
+    }
+
+    #[inline]
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        dealloc(ptr.as_ptr(), layout);
+    }
+}
+
+/// Allocator shared by all storages of a [`World`](crate::world::World).
+#[derive(Clone, Default)]
+pub(crate) enum SharedAllocator {
+    /// The global allocator, without usage tracking.
+    #[default]
+    Global,
+    /// A custom allocator, without usage tracking.
+    Custom(Arc<dyn Allocator>),
+    /// An allocator that tracks the number of bytes allocated through it.
+    Tracked(Arc<TrackedAllocator>),
+}
+
+pub(crate) struct TrackedAllocator {
+    custom: Option<Arc<dyn Allocator>>,
+    allocated: AtomicUsize,
+}
+
+impl SharedAllocator {
+    #[must_use]
+    pub fn new<A>(allocator: A) -> Self
+    where
+        A: Allocator + 'static,
+    {
+        Self::Custom(Arc::new(allocator))
+    }
+
+    /// Returns a new allocator that draws memory from the same source and
+    /// tracks the number of bytes allocated through it.
+    #[must_use]
+    pub fn tracked(&self) -> Self {
+        let custom = match self {
+            Self::Global => None,
+            Self::Custom(allocator) => Some(allocator.clone()),
+            Self::Tracked(allocator) => allocator.custom.clone(),
+        };
+
+        Self::Tracked(Arc::new(TrackedAllocator {
+            custom,
+            allocated: AtomicUsize::new(0),
+        }))
+    }
+
+    /// Returns the number of bytes currently allocated through this allocator,
+    /// or `None` if it does not track its usage.
+    #[must_use]
+    pub fn allocated_bytes(&self) -> Option<usize> {
+        match self {
+            Self::Tracked(allocator) => Some(allocator.allocated.load(Ordering::Relaxed)),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn allocate(&self, layout: Layout) -> NonNull<u8> {
+        let ptr = match self {
+            Self::Global => Global.allocate(layout),
+            Self::Custom(allocator) => allocator.allocate(layout),
+            Self::Tracked(allocator) => {
+                let ptr = match &allocator.custom {
+                    Some(custom) => custom.allocate(layout),
+                    None => Global.allocate(layout),
+                };
+
+                if ptr.is_some() {
+                    allocator
+                        .allocated
+                        .fetch_add(layout.size(), Ordering::Relaxed);
+                }
+
+                ptr
+            }
+        };
+
+        let Some(ptr) = ptr else {
+            handle_alloc_error(layout);
+        };
+
+        ptr
+    }
+
+    pub unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        match self {
+            Self::Global => Global.deallocate(ptr, layout),
+            Self::Custom(allocator) => allocator.deallocate(ptr, layout),
+            Self::Tracked(allocator) => {
+                allocator
+                    .allocated
+                    .fetch_sub(layout.size(), Ordering::Relaxed);
+
+                match &allocator.custom {
+                    Some(custom) => custom.deallocate(ptr, layout),
+                    None => Global.deallocate(ptr, layout),
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Debug for SharedAllocator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Global => f.write_str("Global"),
+            Self::Custom(_) => f.write_str("Custom"),
+            Self::Tracked(allocator) => f
+                .debug_struct("Tracked")
+                .field("custom", &allocator.custom.is_some())
+                .field("allocated", &allocator.allocated)
+                .finish(),
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::allocator::SharedAllocator;
 use crate::component::ComponentSparseSet;
 use core::any::{self, TypeId};
 use core::cmp::Ordering;
@@ -35,8 +36,8 @@ impl ComponentData {
 
     #[inline]
     #[must_use]
-    pub(crate) fn create_sparse_set(&self) -> ComponentSparseSet {
-        self.0.create_sparse_set()
+    pub(crate) fn create_sparse_set(&self, allocator: SharedAllocator) -> ComponentSparseSet {
+        self.0.create_sparse_set(allocator)
     }
 }
 
@@ -91,7 +92,7 @@ unsafe trait AbstractComponentData: Send + Sync + 'static {
     fn type_name(&self) -> &'static str;
 
     #[must_use]
-    fn create_sparse_set(&self) -> ComponentSparseSet;
+    fn create_sparse_set(&self, allocator: SharedAllocator) -> ComponentSparseSet;
 }
 
 struct ComponentDataImpl<T>(PhantomData<*const T>);
@@ -116,7 +117,7 @@ where
         any::type_name::<T>()
     }
 
-    fn create_sparse_set(&self) -> ComponentSparseSet {
-        ComponentSparseSet::new::<T>()
+    fn create_sparse_set(&self, allocator: SharedAllocator) -> ComponentSparseSet {
+        ComponentSparseSet::new::<T>(allocator)
     }
 }
@@ -1,4 +1,4 @@
-use crate::component::{group, panic_missing_comp, ungroup, Component, GroupMask};
+use crate::component::{leave_non_owning, panic_missing_comp, ungroup, Component, GroupMask};
 use crate::entity::Entity;
 use crate::World;
 use core::any::TypeId;
@@ -16,6 +16,12 @@ pub unsafe trait ComponentSet {
     /// Adds the given `components` to `entity`.
     unsafe fn insert(world: &mut World, entity: Entity, components: Self);
 
+    /// Adds the given `components` to `entity`.
+    ///
+    /// Returns the components that were overwritten.
+    #[must_use = "Use `insert` to discard the overwritten components."]
+    unsafe fn replace(world: &mut World, entity: Entity, components: Self) -> Self::Remove;
+
     /// Creates new entities from the components produced by the iterator.
     ///
     /// Returns the newly created entities as a slice.
@@ -23,6 +29,12 @@ pub unsafe trait ComponentSet {
     where
         TComponents: IntoIterator<Item = Self>;
 
+    /// Adds the components produced by the iterator to their paired entities.
+    /// Entities missing from the world are skipped.
+    fn insert_batch<TComponents>(world: &mut World, components: TComponents)
+    where
+        TComponents: IntoIterator<Item = (Entity, Self)>;
+
     /// Removes components from the given `entity`.
     ///
     /// Returns the components that were successfully removed.
@@ -42,9 +54,14 @@ macro_rules! impl_component_set {
             type Remove = ($(Option<$Comp>,)*);
 
             unsafe fn insert(world: &mut World, entity: Entity, components: Self) {
+                let _ = unsafe { Self::replace(world, entity, components) };
+            }
+
+            unsafe fn replace(world: &mut World, entity: Entity, components: Self) -> Self::Remove {
                 let mut group_mask = GroupMask::EMPTY;
+                let mut non_owning_mask = GroupMask::EMPTY;
 
-                $({
+                let replaced = ($({
                     let metadata = world
                         .components
                         .metadata
@@ -52,6 +69,7 @@ macro_rules! impl_component_set {
                         .unwrap_or_else(|| panic_missing_comp::<$Comp>());
 
                     group_mask |= metadata.insert_mask;
+                    non_owning_mask |= metadata.non_owning_mask;
 
                     unsafe {
                         world
@@ -59,20 +77,15 @@ macro_rules! impl_component_set {
                             .components
                             .get_unchecked_mut(metadata.storage_index)
                             .get_mut()
-                            .insert(entity, components.$idx);
+                            .insert(entity, components.$idx)
                     }
-                })*
+                },)*);
 
-                if group_mask != GroupMask::EMPTY {
-                    unsafe {
-                        group(
-                            &mut world.components.components,
-                            &mut world.components.groups,
-                            group_mask,
-                            entity,
-                        );
-                    }
+                unsafe {
+                    world.components.group_entity(group_mask, non_owning_mask, entity);
                 }
+
+                replaced
             }
 
             fn extend<TComponents>(world: &mut World, components: TComponents) -> &[Entity]
@@ -80,6 +93,7 @@ macro_rules! impl_component_set {
                 TComponents: IntoIterator<Item = Self>,
             {
                 let mut group_mask = GroupMask::EMPTY;
+                let mut non_owning_mask = GroupMask::EMPTY;
 
                 let sparse_sets = ($({
                     let metadata = world
@@ -89,6 +103,7 @@ macro_rules! impl_component_set {
                         .unwrap_or_else(|| panic_missing_comp::<$Comp>());
 
                     group_mask |= metadata.insert_mask;
+                    non_owning_mask |= metadata.non_owning_mask;
 
                     unsafe {
                         world
@@ -113,15 +128,10 @@ macro_rules! impl_component_set {
                     world.entities.as_slice().get_unchecked(start_entity..)
                 };
 
-                if group_mask != GroupMask::EMPTY {
+                if group_mask != GroupMask::EMPTY || non_owning_mask != GroupMask::EMPTY {
                     for &entity in new_entities {
                         unsafe {
-                            group(
-                                &mut world.components.components,
-                                &mut world.components.groups,
-                                group_mask,
-                                entity,
-                            );
+                            world.components.group_entity(group_mask, non_owning_mask, entity);
                         }
                     }
                 }
@@ -129,8 +139,50 @@ macro_rules! impl_component_set {
                 new_entities
             }
 
+            fn insert_batch<TComponents>(world: &mut World, components: TComponents)
+            where
+                TComponents: IntoIterator<Item = (Entity, Self)>,
+            {
+                let mut group_mask = GroupMask::EMPTY;
+                let mut non_owning_mask = GroupMask::EMPTY;
+
+                let sparse_sets = ($({
+                    let metadata = world
+                        .components
+                        .metadata
+                        .get(&TypeId::of::<$Comp>())
+                        .unwrap_or_else(|| panic_missing_comp::<$Comp>());
+
+                    group_mask |= metadata.insert_mask;
+                    non_owning_mask |= metadata.non_owning_mask;
+
+                    unsafe {
+                        world
+                            .components
+                            .components
+                            .get_unchecked(metadata.storage_index)
+                            .as_ptr()
+                    }
+                },)*);
+
+                components.into_iter().for_each(|(entity, components)| {
+                    if !world.entities.contains(entity) {
+                        return;
+                    }
+
+                    unsafe {$(
+                        let _ = (*sparse_sets.$idx).insert(entity, components.$idx);
+                    )*}
+                });
+
+                unsafe {
+                    world.components.group_all(group_mask, non_owning_mask);
+                }
+            }
+
             unsafe fn remove(world: &mut World, entity: Entity) -> Self::Remove {
                 let mut group_mask = GroupMask::EMPTY;
+                let mut non_owning_mask = GroupMask::EMPTY;
 
                 let sparse_sets = ($({
                     let metadata = world
@@ -140,6 +192,7 @@ macro_rules! impl_component_set {
                         .unwrap_or_else(|| panic_missing_comp::<$Comp>());
 
                     group_mask |= metadata.delete_mask;
+                    non_owning_mask |= metadata.non_owning_mask;
 
                     unsafe {
                         world
@@ -160,6 +213,14 @@ macro_rules! impl_component_set {
                         );
                     }
 
+                    if non_owning_mask != GroupMask::EMPTY {
+                        leave_non_owning(
+                            &mut world.components.non_owning_groups,
+                            non_owning_mask,
+                            entity,
+                        );
+                    }
+
                     ($(
                         (*sparse_sets.$idx).remove::<$Comp>(entity),
                     )*)
@@ -168,6 +229,7 @@ macro_rules! impl_component_set {
 
             unsafe fn delete(world: &mut World, entity: Entity) {
                 let mut group_mask = GroupMask::EMPTY;
+                let mut non_owning_mask = GroupMask::EMPTY;
 
                 let sparse_sets = ($({
                     let metadata = world
@@ -177,6 +239,7 @@ macro_rules! impl_component_set {
                         .unwrap_or_else(|| panic_missing_comp::<$Comp>());
 
                     group_mask |= metadata.delete_mask;
+                    non_owning_mask |= metadata.non_owning_mask;
 
                     unsafe {
                         world
@@ -197,6 +260,14 @@ macro_rules! impl_component_set {
                         );
                     }
 
+                    if non_owning_mask != GroupMask::EMPTY {
+                        leave_non_owning(
+                            &mut world.components.non_owning_groups,
+                            non_owning_mask,
+                            entity,
+                        );
+                    }
+
                     $(
                         (*sparse_sets.$idx).delete::<$Comp>(entity);
                     )*
@@ -214,6 +285,11 @@ unsafe impl ComponentSet for () {
         // Empty
     }
 
+    #[inline(always)]
+    unsafe fn replace(_world: &mut World, _entity: Entity, _components: Self) -> Self::Remove {
+        // Empty
+    }
+
     fn extend<TComponents>(world: &mut World, components: TComponents) -> &[Entity]
     where
         TComponents: IntoIterator<Item = Self>,
@@ -227,6 +303,13 @@ unsafe impl ComponentSet for () {
         unsafe { world.entities.as_slice().get_unchecked(start_entity..) }
     }
 
+    fn insert_batch<TComponents>(_world: &mut World, components: TComponents)
+    where
+        TComponents: IntoIterator<Item = (Entity, Self)>,
+    {
+        components.into_iter().for_each(drop);
+    }
+
     #[inline(always)]
     unsafe fn remove(_world: &mut World, _entity: Entity) -> Self::Remove {
         // Empty
@@ -258,4 +341,36 @@ mod impls {
     impl_component_set!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7), (I, 8), (J, 9), (K, 10), (L, 11), (M, 12), (N, 13));
     impl_component_set!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7), (I, 8), (J, 9), (K, 10), (L, 11), (M, 12), (N, 13), (O, 14));
     impl_component_set!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7), (I, 8), (J, 9), (K, 10), (L, 11), (M, 12), (N, 13), (O, 14), (P, 15));
+    #[cfg(feature = "wide-groups")]
+    impl_component_set!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7), (I, 8), (J, 9), (K, 10), (L, 11), (M, 12), (N, 13), (O, 14), (P, 15), (Q, 16));
+    #[cfg(feature = "wide-groups")]
+    impl_component_set!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7), (I, 8), (J, 9), (K, 10), (L, 11), (M, 12), (N, 13), (O, 14), (P, 15), (Q, 16), (R, 17));
+    #[cfg(feature = "wide-groups")]
+    impl_component_set!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7), (I, 8), (J, 9), (K, 10), (L, 11), (M, 12), (N, 13), (O, 14), (P, 15), (Q, 16), (R, 17), (S, 18));
+    #[cfg(feature = "wide-groups")]
+    impl_component_set!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7), (I, 8), (J, 9), (K, 10), (L, 11), (M, 12), (N, 13), (O, 14), (P, 15), (Q, 16), (R, 17), (S, 18), (T, 19));
+    #[cfg(feature = "wide-groups")]
+    impl_component_set!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7), (I, 8), (J, 9), (K, 10), (L, 11), (M, 12), (N, 13), (O, 14), (P, 15), (Q, 16), (R, 17), (S, 18), (T, 19), (U, 20));
+    #[cfg(feature = "wide-groups")]
+    impl_component_set!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7), (I, 8), (J, 9), (K, 10), (L, 11), (M, 12), (N, 13), (O, 14), (P, 15), (Q, 16), (R, 17), (S, 18), (T, 19), (U, 20), (V, 21));
+    #[cfg(feature = "wide-groups")]
+    impl_component_set!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7), (I, 8), (J, 9), (K, 10), (L, 11), (M, 12), (N, 13), (O, 14), (P, 15), (Q, 16), (R, 17), (S, 18), (T, 19), (U, 20), (V, 21), (W, 22));
+    #[cfg(feature = "wide-groups")]
+    impl_component_set!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7), (I, 8), (J, 9), (K, 10), (L, 11), (M, 12), (N, 13), (O, 14), (P, 15), (Q, 16), (R, 17), (S, 18), (T, 19), (U, 20), (V, 21), (W, 22), (X, 23));
+    #[cfg(feature = "wide-groups")]
+    impl_component_set!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7), (I, 8), (J, 9), (K, 10), (L, 11), (M, 12), (N, 13), (O, 14), (P, 15), (Q, 16), (R, 17), (S, 18), (T, 19), (U, 20), (V, 21), (W, 22), (X, 23), (Y, 24));
+    #[cfg(feature = "wide-groups")]
+    impl_component_set!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7), (I, 8), (J, 9), (K, 10), (L, 11), (M, 12), (N, 13), (O, 14), (P, 15), (Q, 16), (R, 17), (S, 18), (T, 19), (U, 20), (V, 21), (W, 22), (X, 23), (Y, 24), (Z, 25));
+    #[cfg(feature = "wide-groups")]
+    impl_component_set!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7), (I, 8), (J, 9), (K, 10), (L, 11), (M, 12), (N, 13), (O, 14), (P, 15), (Q, 16), (R, 17), (S, 18), (T, 19), (U, 20), (V, 21), (W, 22), (X, 23), (Y, 24), (Z, 25), (AA, 26));
+    #[cfg(feature = "wide-groups")]
+    impl_component_set!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7), (I, 8), (J, 9), (K, 10), (L, 11), (M, 12), (N, 13), (O, 14), (P, 15), (Q, 16), (R, 17), (S, 18), (T, 19), (U, 20), (V, 21), (W, 22), (X, 23), (Y, 24), (Z, 25), (AA, 26), (AB, 27));
+    #[cfg(feature = "wide-groups")]
+    impl_component_set!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7), (I, 8), (J, 9), (K, 10), (L, 11), (M, 12), (N, 13), (O, 14), (P, 15), (Q, 16), (R, 17), (S, 18), (T, 19), (U, 20), (V, 21), (W, 22), (X, 23), (Y, 24), (Z, 25), (AA, 26), (AB, 27), (AC, 28));
+    #[cfg(feature = "wide-groups")]
+    impl_component_set!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7), (I, 8), (J, 9), (K, 10), (L, 11), (M, 12), (N, 13), (O, 14), (P, 15), (Q, 16), (R, 17), (S, 18), (T, 19), (U, 20), (V, 21), (W, 22), (X, 23), (Y, 24), (Z, 25), (AA, 26), (AB, 27), (AC, 28), (AD, 29));
+    #[cfg(feature = "wide-groups")]
+    impl_component_set!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7), (I, 8), (J, 9), (K, 10), (L, 11), (M, 12), (N, 13), (O, 14), (P, 15), (Q, 16), (R, 17), (S, 18), (T, 19), (U, 20), (V, 21), (W, 22), (X, 23), (Y, 24), (Z, 25), (AA, 26), (AB, 27), (AC, 28), (AD, 29), (AE, 30));
+    #[cfg(feature = "wide-groups")]
+    impl_component_set!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7), (I, 8), (J, 9), (K, 10), (L, 11), (M, 12), (N, 13), (O, 14), (P, 15), (Q, 16), (R, 17), (S, 18), (T, 19), (U, 20), (V, 21), (W, 22), (X, 23), (Y, 24), (Z, 25), (AA, 26), (AB, 27), (AC, 28), (AD, 29), (AE, 30), (AF, 31));
 }
@@ -1,6 +1,8 @@
-use crate::component::Component;
+use crate::allocator::SharedAllocator;
+use crate::component::{BoxedComponent, Component, ComponentData};
 use crate::entity::{Entity, SparseVec, SparseVecSlot};
-use alloc::{alloc, Layout, LayoutError};
+use alloc::alloc::{Layout, LayoutError};
+use alloc::boxed::Box;
 use core::ptr::NonNull;
 use core::{fmt, mem, slice};
 
@@ -11,27 +13,45 @@ pub(crate) struct ComponentSparseSet {
     len: usize,
     cap: usize,
     vtable: ComponentSparseSetVtable,
+    allocator: SharedAllocator,
 }
 
 impl ComponentSparseSet {
     #[must_use]
-    pub const fn new<T>() -> Self
+    pub fn new<T>(allocator: SharedAllocator) -> Self
     where
         T: Component,
     {
         Self {
-            sparse: SparseVec::new(),
+            sparse: SparseVec::new_in(allocator.clone()),
             entities: NonNull::dangling(),
             components: NonNull::<T>::dangling().cast(),
             len: 0,
             cap: 0,
             vtable: ComponentSparseSetVtable::new::<T>(),
+            allocator,
+        }
+    }
+
+    #[must_use]
+    pub fn new_local<T>(allocator: SharedAllocator) -> Self
+    where
+        T: 'static,
+    {
+        Self {
+            sparse: SparseVec::new_in(allocator.clone()),
+            entities: NonNull::dangling(),
+            components: NonNull::<T>::dangling().cast(),
+            len: 0,
+            cap: 0,
+            vtable: ComponentSparseSetVtable::new_local::<T>(),
+            allocator,
         }
     }
 
     pub unsafe fn insert<T>(&mut self, entity: Entity, component: T) -> Option<T>
     where
-        T: Component,
+        T: 'static,
     {
         let slot = self.sparse.get_mut_or_allocate_at(entity.sparse());
 
@@ -62,7 +82,7 @@ impl ComponentSparseSet {
 
     pub unsafe fn remove<T>(&mut self, entity: Entity) -> Option<T>
     where
-        T: Component,
+        T: 'static,
     {
         let raw_index = self.sparse.remove(entity)?;
         let index = raw_index as usize;
@@ -89,7 +109,7 @@ impl ComponentSparseSet {
 
     pub unsafe fn delete<T>(&mut self, entity: Entity)
     where
-        T: Component,
+        T: 'static,
     {
         let Some(raw_index) = self.sparse.remove(entity) else {
             return;
@@ -115,6 +135,24 @@ impl ComponentSparseSet {
         last_ptr.copy_to(dropped_ptr, 1);
     }
 
+    #[must_use]
+    pub unsafe fn take_all<T, C>(&mut self) -> C
+    where
+        T: 'static,
+        C: FromIterator<(Entity, T)>,
+    {
+        self.sparse.clear();
+        let len = mem::replace(&mut self.len, 0);
+
+        (0..len)
+            .map(|i| {
+                let entity = *self.entities.add(i).as_ref();
+                let component = self.components.cast::<T>().add(i).read();
+                (entity, component)
+            })
+            .collect()
+    }
+
     #[inline]
     pub fn delete_dyn(&mut self, entity: Entity) {
         unsafe {
@@ -122,11 +160,26 @@ impl ComponentSparseSet {
         }
     }
 
+    #[inline]
+    #[must_use]
+    pub fn take_dyn(&mut self, entity: Entity) -> Option<(ComponentData, BoxedComponent)> {
+        unsafe { (self.vtable.take)(self, entity) }
+    }
+
+    /// The `component` must have the same type as the components in the
+    /// sparse set.
+    #[inline]
+    pub unsafe fn insert_dyn(&mut self, entity: Entity, component: BoxedComponent) {
+        unsafe {
+            (self.vtable.insert)(self, entity, component);
+        }
+    }
+
     #[inline]
     #[must_use]
     pub unsafe fn get<T>(&self, entity: Entity) -> Option<&T>
     where
-        T: Component,
+        T: 'static,
     {
         let dense = self.sparse.get(entity)? as usize;
         Some(self.components.cast::<T>().add(dense).as_ref())
@@ -134,9 +187,10 @@ impl ComponentSparseSet {
 
     #[inline]
     #[must_use]
+    #[allow(clippy::mut_from_ref)]
     pub unsafe fn get_mut<T>(&self, entity: Entity) -> Option<&mut T>
     where
-        T: Component,
+        T: 'static,
     {
         let dense = self.sparse.get(entity)? as usize;
         Some(self.components.cast::<T>().add(dense).as_mut())
@@ -164,7 +218,7 @@ impl ComponentSparseSet {
     #[must_use]
     pub unsafe fn as_slice<T>(&self) -> &[T]
     where
-        T: Component,
+        T: 'static,
     {
         slice::from_raw_parts(self.components.cast::<T>().as_ptr(), self.len)
     }
@@ -173,7 +227,7 @@ impl ComponentSparseSet {
     #[must_use]
     pub unsafe fn as_mut_slice<T>(&mut self) -> &mut [T]
     where
-        T: Component,
+        T: 'static,
     {
         slice::from_raw_parts_mut(self.components.cast::<T>().as_ptr(), self.len)
     }
@@ -182,7 +236,7 @@ impl ComponentSparseSet {
     #[must_use]
     pub unsafe fn as_non_null_ptr<T>(&self) -> NonNull<T>
     where
-        T: Component,
+        T: 'static,
     {
         self.components.cast::<T>()
     }
@@ -224,7 +278,7 @@ impl ComponentSparseSet {
     #[inline(never)]
     unsafe fn grow_typed<T>(&mut self)
     where
-        T: Component,
+        T: 'static,
     {
         // Allocate new storage for entities and components.
         let (new_entities, new_components, new_cap) = {
@@ -239,9 +293,7 @@ impl ComponentSparseSet {
 
             let (new_layout, new_components_offset) = Self::compute_layout::<T>(new_cap);
 
-            let Some(new_data) = NonNull::new(alloc::alloc(new_layout)) else {
-                alloc::handle_alloc_error(new_layout);
-            };
+            let new_data = self.allocator.allocate(new_layout);
 
             (
                 new_data.cast::<Entity>(),
@@ -260,7 +312,7 @@ impl ComponentSparseSet {
         // Deallocate old storage, if any.
         if self.cap != 0 {
             let (layout, _) = Self::compute_layout::<T>(self.cap);
-            alloc::dealloc(self.entities.cast().as_ptr(), layout);
+            self.allocator.deallocate(self.entities.cast(), layout);
         }
 
         // Update pointers and capacity.
@@ -271,7 +323,7 @@ impl ComponentSparseSet {
 
     unsafe fn swap_typed<T>(&mut self, dense_a: usize, dense_b: usize)
     where
-        T: Component,
+        T: 'static,
     {
         debug_assert!(dense_a < self.len);
         debug_assert!(dense_b < self.len);
@@ -290,9 +342,37 @@ impl ComponentSparseSet {
         mem::swap(component_a, component_b);
     }
 
-    unsafe fn clear_typed<T>(&mut self)
+    unsafe fn take_typed<T>(&mut self, entity: Entity) -> Option<(ComponentData, BoxedComponent)>
     where
         T: Component,
+    {
+        let component = self.remove::<T>(entity)?;
+        Some((ComponentData::new::<T>(), Box::new(component)))
+    }
+
+    unsafe fn take_local_typed<T>(
+        &mut self,
+        entity: Entity,
+    ) -> Option<(ComponentData, BoxedComponent)>
+    where
+        T: 'static,
+    {
+        // Local components cannot be boxed as `Send + Sync`, so they are dropped.
+        self.delete::<T>(entity);
+        None
+    }
+
+    unsafe fn insert_typed<T>(&mut self, entity: Entity, component: BoxedComponent)
+    where
+        T: 'static,
+    {
+        let component = component.downcast::<T>().unwrap_unchecked();
+        let _ = self.insert(entity, *component);
+    }
+
+    unsafe fn clear_typed<T>(&mut self)
+    where
+        T: 'static,
     {
         self.sparse.clear();
 
@@ -309,7 +389,7 @@ impl ComponentSparseSet {
 
     unsafe fn drop_typed<T>(&mut self)
     where
-        T: Component,
+        T: 'static,
     {
         if mem::needs_drop::<T>() {
             for i in 0..self.len {
@@ -321,7 +401,7 @@ impl ComponentSparseSet {
 
         if self.cap != 0 {
             let (layout, _) = Self::compute_layout::<T>(self.cap);
-            alloc::dealloc(self.entities.cast::<u8>().as_ptr(), layout);
+            self.allocator.deallocate(self.entities.cast(), layout);
         }
     }
 
@@ -369,6 +449,8 @@ struct ComponentSparseSetVtable {
     grow: unsafe fn(&mut ComponentSparseSet),
     swap: unsafe fn(&mut ComponentSparseSet, usize, usize),
     delete: unsafe fn(&mut ComponentSparseSet, Entity),
+    take: unsafe fn(&mut ComponentSparseSet, Entity) -> Option<(ComponentData, BoxedComponent)>,
+    insert: unsafe fn(&mut ComponentSparseSet, Entity, BoxedComponent),
     clear: unsafe fn(&mut ComponentSparseSet),
     drop: unsafe fn(&mut ComponentSparseSet),
 }
@@ -382,6 +464,23 @@ impl ComponentSparseSetVtable {
             grow: ComponentSparseSet::grow_typed::<T>,
             swap: ComponentSparseSet::swap_typed::<T>,
             delete: ComponentSparseSet::delete::<T>,
+            take: ComponentSparseSet::take_typed::<T>,
+            insert: ComponentSparseSet::insert_typed::<T>,
+            clear: ComponentSparseSet::clear_typed::<T>,
+            drop: ComponentSparseSet::drop_typed::<T>,
+        }
+    }
+
+    const fn new_local<T>() -> Self
+    where
+        T: 'static,
+    {
+        Self {
+            grow: ComponentSparseSet::grow_typed::<T>,
+            swap: ComponentSparseSet::swap_typed::<T>,
+            delete: ComponentSparseSet::delete::<T>,
+            take: ComponentSparseSet::take_local_typed::<T>,
+            insert: ComponentSparseSet::insert_typed::<T>,
             clear: ComponentSparseSet::clear_typed::<T>,
             drop: ComponentSparseSet::drop_typed::<T>,
         }
@@ -1,53 +1,105 @@
+use crate::allocator::SharedAllocator;
 use crate::component::{
-    group, ungroup_all, Component, ComponentData, ComponentSparseSet, Group, GroupInfo,
-    GroupLayout, GroupMask, GroupMetadata, NonZeroStorageMask, QueryGroupInfo, QueryMask,
-    StorageMask, View, ViewMut,
+    group, group_all, join_non_owning, rebuild_non_owning, ungroup_all, Component, ComponentData,
+    ComponentSparseSet, EntityComponents, Group, GroupEvent, GroupFamily, GroupInfo, GroupLayout,
+    GroupMask, GroupMetadata, GroupSummary, NonOwningGroup, NonOwningMatch, NonZeroStorageMask,
+    QueryGroupInfo, QueryMask, StorageMask, View, ViewMut,
 };
-use crate::entity::Entity;
+use crate::entity::{Entity, EntitySparseSet};
+use crate::query::DenseRejection;
 use alloc::vec::Vec;
 use atomic_refcell::AtomicRefCell;
 use core::any::{self, TypeId};
 use core::ops::Range;
+use core::sync::atomic::{AtomicU64, Ordering};
 use core::{cmp, mem};
 use hashbrown::hash_map::Entry;
 use hashbrown::HashMap;
 use rustc_hash::FxBuildHasher;
 
-type FxHashMap<K, V> = HashMap<K, V, FxBuildHasher>;
+pub(crate) type FxHashMap<K, V> = HashMap<K, V, FxBuildHasher>;
+
+/// Source of the unique ids of component storages. Zero is never used.
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
 
 #[derive(Default, Debug)]
 pub(crate) struct ComponentStorage {
     pub(crate) groups: Vec<Group>,
     pub(crate) metadata: FxHashMap<TypeId, ComponentMetadata>,
     pub(crate) components: Vec<AtomicRefCell<ComponentSparseSet>>,
+    pub(crate) non_owning_groups: Vec<NonOwningGroup>,
+    pub(crate) allocator: SharedAllocator,
+    pub(crate) layout: GroupLayout,
+    /// Groups that must be completed when grouping is no longer deferred.
+    pub(crate) deferred: Option<DeferredGroups>,
+    /// Unique nonzero id of the storage, assigned on creation.
+    pub(crate) id: u64,
+    /// Incremented every time storages are added or the groups are rebuilt.
+    pub(crate) generation: u64,
+}
+
+#[derive(Clone, Copy, Default, Debug)]
+pub(crate) struct DeferredGroups {
+    pub group_mask: GroupMask,
+    pub non_owning_mask: GroupMask,
 }
 
 impl ComponentStorage {
     #[must_use]
-    pub fn new(layout: &GroupLayout) -> Self {
-        let mut storage = Self::default();
-
-        unsafe {
-            storage.set_layout(layout, &[]);
-        }
+    pub fn new(layout: &GroupLayout, allocator: SharedAllocator) -> Self {
+        let mut storage = Self {
+            allocator,
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            ..Default::default()
+        };
 
+        storage.set_layout(layout);
         storage
     }
 
-    pub unsafe fn set_layout(&mut self, layout: &GroupLayout, entities: &[Entity]) {
+    /// Sets a new group layout. Groups that are also present in the current
+    /// layout keep their grouped entities and tracked events, while new groups
+    /// only group the entities that are not already at the front of their
+    /// storages.
+    pub fn set_layout(&mut self, layout: &GroupLayout) {
+        let old_layout = mem::replace(&mut self.layout, layout.clone());
+        self.generation += 1;
+        let mut old_groups = mem::take(&mut self.groups);
+        let old_non_owning_groups = mem::take(&mut self.non_owning_groups);
         let mut sparse_sets = self.extract_sparse_sets();
 
+        let mut regroup_mask = GroupMask::EMPTY;
+
         for family in layout.families() {
             let storage_start = self.components.len();
             let group_start = self.groups.len();
             let group_end = group_start + family.arities().len();
 
+            let old_indexes = kept_group_indexes(family, &old_layout);
+
             let mut prev_arity = 0;
 
-            for &arity in family.arities() {
+            for (i, &arity) in family.arities().iter().enumerate() {
                 let storage_end = storage_start + arity;
                 let new_group_start = self.groups.len();
 
+                let (len, events) = if let Some(old_index) = old_indexes[i] {
+                    let old_group = &mut old_groups[old_index];
+                    (old_group.len, old_group.events.take())
+                } else {
+                    regroup_mask |= GroupMask::single(new_group_start);
+
+                    // The entities of the next kept group in the family are
+                    // already at the front of the storages of this group.
+                    let len = old_indexes[(i + 1)..]
+                        .iter()
+                        .flatten()
+                        .next()
+                        .map_or(0, |&old_index| old_groups[old_index].len);
+
+                    (len, None)
+                };
+
                 self.groups.push(Group {
                     metadata: GroupMetadata {
                         storage_start,
@@ -57,7 +109,8 @@ impl ComponentStorage {
                         include_mask: QueryMask::include(arity),
                         exclude_mask: QueryMask::exclude(prev_arity, arity),
                     },
-                    len: 0,
+                    len,
+                    events,
                 });
 
                 for local_storage_index in prev_arity..arity {
@@ -69,6 +122,7 @@ impl ComponentStorage {
                             storage_index: self.components.len(),
                             insert_mask: GroupMask::from_to(group_start, group_end),
                             delete_mask: GroupMask::from_to(new_group_start, group_end),
+                            non_owning_mask: GroupMask::EMPTY,
                             group_info: Some(GroupInfo {
                                 group_start: group_start as u8,
                                 group_end: self.groups.len() as u8,
@@ -79,7 +133,7 @@ impl ComponentStorage {
 
                     let sparse_set = sparse_sets
                         .remove(&component.type_id())
-                        .unwrap_or_else(|| component.create_sparse_set());
+                        .unwrap_or_else(|| component.create_sparse_set(self.allocator.clone()));
 
                     self.components.push(AtomicRefCell::new(sparse_set));
                 }
@@ -95,6 +149,7 @@ impl ComponentStorage {
                     storage_index: self.components.len(),
                     insert_mask: GroupMask::default(),
                     delete_mask: GroupMask::default(),
+                    non_owning_mask: GroupMask::EMPTY,
                     group_info: None,
                 },
             );
@@ -102,13 +157,284 @@ impl ComponentStorage {
             self.components.push(AtomicRefCell::new(sparse_set));
         }
 
-        let group_mask = GroupMask::from_to(0, self.groups.len());
+        let rebuild_mask = self.set_non_owning_groups(layout, old_non_owning_groups);
 
-        for &entity in entities {
+        if let Some(deferred) = &mut self.deferred {
+            // Group indexes changed, so every group is completed at the end.
+            *deferred = DeferredGroups {
+                group_mask: GroupMask::from_to(0, self.groups.len()),
+                non_owning_mask: GroupMask::from_to(0, self.non_owning_groups.len()),
+            };
+        } else {
             unsafe {
-                group(&mut self.components, &mut self.groups, group_mask, entity);
+                group_all(&mut self.components, &mut self.groups, regroup_mask);
+                rebuild_non_owning(&self.components, &mut self.non_owning_groups, rebuild_mask);
+            }
+        }
+    }
+
+    /// Stops grouping entities as their components are inserted, until
+    /// [`finish_deferred_grouping`](Self::finish_deferred_grouping) is called.
+    ///
+    /// Returns whether grouping was not already deferred.
+    pub fn defer_grouping(&mut self) -> bool {
+        if self.deferred.is_some() {
+            return false;
+        }
+
+        self.deferred = Some(DeferredGroups::default());
+        true
+    }
+
+    /// Groups all entities whose grouping was deferred, in one pass over the
+    /// storages of each affected group.
+    pub fn finish_deferred_grouping(&mut self) {
+        let Some(deferred) = self.deferred.take() else {
+            return;
+        };
+
+        unsafe {
+            group_all(&mut self.components, &mut self.groups, deferred.group_mask);
+
+            rebuild_non_owning(
+                &self.components,
+                &mut self.non_owning_groups,
+                deferred.non_owning_mask,
+            );
+        }
+    }
+
+    /// Adds `entity` to the groups in the masks whose components it now has,
+    /// or records the masks if grouping is deferred.
+    pub unsafe fn group_entity(
+        &mut self,
+        group_mask: GroupMask,
+        non_owning_mask: GroupMask,
+        entity: Entity,
+    ) {
+        if let Some(deferred) = &mut self.deferred {
+            deferred.group_mask |= group_mask;
+            deferred.non_owning_mask |= non_owning_mask;
+            return;
+        }
+
+        if group_mask != GroupMask::EMPTY {
+            group(&mut self.components, &mut self.groups, group_mask, entity);
+        }
+
+        if non_owning_mask != GroupMask::EMPTY {
+            join_non_owning(
+                &self.components,
+                &mut self.non_owning_groups,
+                non_owning_mask,
+                entity,
+            );
+        }
+    }
+
+    /// Groups all complete entities of the groups in the masks, or records the
+    /// masks if grouping is deferred.
+    pub unsafe fn group_all(&mut self, group_mask: GroupMask, non_owning_mask: GroupMask) {
+        if let Some(deferred) = &mut self.deferred {
+            deferred.group_mask |= group_mask;
+            deferred.non_owning_mask |= non_owning_mask;
+            return;
+        }
+
+        if group_mask != GroupMask::EMPTY {
+            group_all(&mut self.components, &mut self.groups, group_mask);
+        }
+
+        if non_owning_mask != GroupMask::EMPTY {
+            rebuild_non_owning(
+                &self.components,
+                &mut self.non_owning_groups,
+                non_owning_mask,
+            );
+        }
+    }
+
+    /// Sets the non-owning groups of `layout`, reusing the entity lists of
+    /// `old_groups` with the same components.
+    ///
+    /// Returns the mask of the groups that must be rebuilt.
+    #[must_use]
+    fn set_non_owning_groups(
+        &mut self,
+        layout: &GroupLayout,
+        mut old_groups: Vec<NonOwningGroup>,
+    ) -> GroupMask {
+        let mut rebuild_mask = GroupMask::EMPTY;
+
+        for (i, components) in layout.non_owning_groups().iter().enumerate() {
+            let group_mask = GroupMask::single(i);
+            let mut storages = Vec::with_capacity(components.len());
+
+            for &component in components {
+                self.register_dyn(component);
+
+                let metadata = self.metadata.get_mut(&component.type_id()).unwrap();
+                metadata.non_owning_mask |= group_mask;
+                storages.push(metadata.storage_index);
             }
+
+            // Entity lists do not depend on storage order, so they are reused.
+            let old_index = old_groups
+                .iter()
+                .position(|group| group.components == *components);
+
+            let entities = if let Some(old_index) = old_index {
+                old_groups.swap_remove(old_index).entities
+            } else {
+                rebuild_mask |= group_mask;
+                EntitySparseSet::new_in(self.allocator.clone())
+            };
+
+            self.non_owning_groups.push(NonOwningGroup {
+                components: components.clone(),
+                storages,
+                entities,
+            });
         }
+
+        rebuild_mask
+    }
+
+    pub fn groups(&self) -> impl Iterator<Item = GroupSummary<'_>> + '_ {
+        let families = self
+            .layout
+            .families()
+            .iter()
+            .enumerate()
+            .flat_map(|(family, f)| {
+                f.arities()
+                    .iter()
+                    .map(move |&arity| (family, &f.components()[..arity]))
+            });
+
+        self.groups.iter().zip(families).enumerate().map(
+            |(index, (group, (family, components)))| GroupSummary {
+                index,
+                family,
+                components,
+                len: group.len,
+            },
+        )
+    }
+
+    /// Sorts the components of a sorted group with its comparator.
+    pub fn sort_group(&mut self, components: &[ComponentData]) {
+        let Some(sort) = self.layout.group_sort(components) else {
+            panic_unsorted_group(components);
+        };
+
+        let Some(sort_metadata) = self.metadata.get(&sort.component().type_id()) else {
+            panic_missing_comp_dyn(sort.component());
+        };
+
+        let Some(group_index) = self.group_index(components) else {
+            panic_missing_group(components);
+        };
+
+        let group = &self.groups[group_index];
+        let storage_range = group.metadata.storage_range();
+
+        let mut indexes = unsafe {
+            let sort_storage = self.components[sort_metadata.storage_index].get_mut();
+            sort.sort_indexes(sort_storage, group.len)
+        };
+
+        let storages = &mut self.components[storage_range];
+
+        // Apply the permutation in place, following each cycle with swaps.
+        // Placed indexes are marked by pointing them to themselves.
+        for start in 0..indexes.len() {
+            let mut i = start;
+
+            while indexes[i] != start {
+                let j = indexes[i];
+
+                for storage in storages.iter_mut() {
+                    unsafe {
+                        storage.get_mut().swap(i, j);
+                    }
+                }
+
+                indexes[i] = i;
+                i = j;
+            }
+
+            indexes[i] = i;
+        }
+    }
+
+    /// Starts recording the entities that enter or leave the given group.
+    ///
+    /// Returns whether the group was not already tracked.
+    pub fn track_group(&mut self, components: &[ComponentData]) -> bool {
+        let Some(group_index) = self.group_index(components) else {
+            panic_missing_group(components);
+        };
+
+        let group = &mut self.groups[group_index];
+
+        if group.events.is_some() {
+            return false;
+        }
+
+        group.events = Some(Vec::new());
+        true
+    }
+
+    /// Stops recording the membership changes of the given group and discards
+    /// the events that were not drained.
+    ///
+    /// Returns whether the group was tracked.
+    pub fn untrack_group(&mut self, components: &[ComponentData]) -> bool {
+        let Some(group_index) = self.group_index(components) else {
+            panic_missing_group(components);
+        };
+
+        self.groups[group_index].events.take().is_some()
+    }
+
+    /// Removes and returns the events recorded for the given group, in the
+    /// order in which they happened.
+    pub fn drain_group_events(
+        &mut self,
+        components: &[ComponentData],
+    ) -> impl Iterator<Item = GroupEvent> + '_ {
+        let Some(group_index) = self.group_index(components) else {
+            panic_missing_group(components);
+        };
+
+        self.groups[group_index]
+            .events
+            .iter_mut()
+            .flat_map(|events| events.drain(..))
+    }
+
+    /// Returns the index of the group made of exactly the given components.
+    #[must_use]
+    fn group_index(&self, components: &[ComponentData]) -> Option<usize> {
+        let mut group_start = 0;
+
+        for family in self.layout.families() {
+            let index = family.arities().iter().position(|&arity| {
+                arity == components.len()
+                    && family.components()[..arity]
+                        .iter()
+                        .all(|c| components.contains(c))
+            });
+
+            if let Some(index) = index {
+                return Some(group_start + index);
+            }
+
+            group_start += family.arities().len();
+        }
+
+        None
     }
 
     pub fn register_dyn(&mut self, component: ComponentData) -> bool {
@@ -120,12 +446,15 @@ impl ComponentStorage {
             storage_index: self.components.len(),
             insert_mask: GroupMask::default(),
             delete_mask: GroupMask::default(),
+            non_owning_mask: GroupMask::EMPTY,
             group_info: None,
         });
 
-        self.components
-            .push(AtomicRefCell::new(component.create_sparse_set()));
+        self.components.push(AtomicRefCell::new(
+            component.create_sparse_set(self.allocator.clone()),
+        ));
 
+        self.generation += 1;
         true
     }
 
@@ -140,23 +469,104 @@ impl ComponentStorage {
             ungroup_all(&mut self.components, &mut self.groups, entity);
         }
 
+        for group in &mut self.non_owning_groups {
+            group.entities.remove(entity);
+        }
+
         for sparse_set in &mut self.components {
             sparse_set.get_mut().delete_dyn(entity);
         }
     }
 
+    #[must_use]
+    pub fn take(&mut self, entity: Entity) -> EntityComponents {
+        unsafe {
+            ungroup_all(&mut self.components, &mut self.groups, entity);
+        }
+
+        for group in &mut self.non_owning_groups {
+            group.entities.remove(entity);
+        }
+
+        let mut components = EntityComponents::new();
+
+        for sparse_set in &mut self.components {
+            if let Some((data, component)) = sparse_set.get_mut().take_dyn(entity) {
+                components.push(data, component);
+            }
+        }
+
+        components
+    }
+
+    /// Panics if any of the `components` is not registered.
+    pub fn ensure_registered_dyn(&self, components: &EntityComponents) {
+        for data in components.types() {
+            if !self.metadata.contains_key(&data.type_id()) {
+                panic_missing_comp_dyn(data);
+            }
+        }
+    }
+
+    /// Inserts the `components` of `entity`, which must have been checked with
+    /// [`ensure_registered_dyn`](Self::ensure_registered_dyn) first.
+    pub fn insert_dyn(&mut self, entity: Entity, components: EntityComponents) {
+        let mut group_mask = GroupMask::EMPTY;
+        let mut non_owning_mask = GroupMask::EMPTY;
+
+        for (data, component) in components.into_inner() {
+            let Some(metadata) = self.metadata.get(&data.type_id()) else {
+                panic_missing_comp_dyn(data);
+            };
+
+            group_mask |= metadata.insert_mask;
+            non_owning_mask |= metadata.non_owning_mask;
+
+            unsafe {
+                self.components
+                    .get_unchecked_mut(metadata.storage_index)
+                    .get_mut()
+                    .insert_dyn(entity, component);
+            }
+        }
+
+        unsafe {
+            self.group_entity(group_mask, non_owning_mask, entity);
+        }
+    }
+
     pub fn clear(&mut self) {
         for group in &mut self.groups {
+            group.record_clear(&mut self.components);
             group.len = 0;
         }
 
+        for group in &mut self.non_owning_groups {
+            group.entities.clear();
+        }
+
         for sparse_set in &mut self.components {
             sparse_set.get_mut().clear();
         }
     }
 
+    pub fn clear_component<T>(&mut self)
+    where
+        T: Component,
+    {
+        self.ungroup_storage::<T>().clear();
+    }
+
+    #[must_use]
+    pub fn take_component<T>(&mut self) -> Vec<(Entity, T)>
+    where
+        T: Component,
+    {
+        unsafe { self.ungroup_storage::<T>().take_all() }
+    }
+
     #[must_use]
-    pub fn borrow<T>(&self) -> View<T>
+    pub fn borrow<T>(&self) -> View<'_, T>
     where
         T: Component,
     {
@@ -174,7 +584,7 @@ impl ComponentStorage {
     }
 
     #[must_use]
-    pub fn borrow_mut<T>(&self) -> ViewMut<T>
+    pub fn borrow_mut<T>(&self) -> ViewMut<'_, T>
     where
         T: Component,
     {
@@ -191,8 +601,52 @@ impl ComponentStorage {
         }
     }
 
+    /// Returns the index of the storage of `T`.
     #[must_use]
-    pub fn borrow_with_group_info<T>(&self) -> (View<T>, Option<GroupInfo>)
+    pub fn storage_index<T>(&self) -> usize
+    where
+        T: Component,
+    {
+        let Some(metadata) = self.metadata.get(&TypeId::of::<T>()) else {
+            panic_missing_comp::<T>();
+        };
+
+        metadata.storage_index
+    }
+
+    /// Returns the group info of the given `components`, or `None` if there
+    /// are no components or they are not all part of the same group family.
+    #[must_use]
+    pub fn components_group_info(&self, components: &[ComponentData]) -> Option<GroupInfo> {
+        let mut infos = components
+            .iter()
+            .map(|component| self.metadata.get(&component.type_id())?.group_info);
+
+        let first = infos.next()??;
+        infos.try_fold(first, |info, other| info.add_group(other?))
+    }
+
+    /// Borrows the storage at `index`, which must hold components of type `T`.
+    #[must_use]
+    pub unsafe fn borrow_at<T>(&self, index: usize) -> View<'_, T>
+    where
+        T: Component,
+    {
+        View::new(self.components.get_unchecked(index).borrow())
+    }
+
+    /// Mutably borrows the storage at `index`, which must hold components of
+    /// type `T`.
+    #[must_use]
+    pub unsafe fn borrow_mut_at<T>(&self, index: usize) -> ViewMut<'_, T>
+    where
+        T: Component,
+    {
+        ViewMut::new(self.components.get_unchecked(index).borrow_mut())
+    }
+
+    #[must_use]
+    pub fn borrow_with_group_info<T>(&self) -> (View<'_, T>, Option<GroupInfo>)
     where
         T: Component,
     {
@@ -212,7 +666,7 @@ impl ComponentStorage {
     }
 
     #[must_use]
-    pub fn borrow_with_group_info_mut<T>(&self) -> (ViewMut<T>, Option<GroupInfo>)
+    pub fn borrow_with_group_info_mut<T>(&self) -> (ViewMut<'_, T>, Option<GroupInfo>)
     where
         T: Component,
     {
@@ -231,52 +685,79 @@ impl ComponentStorage {
         (view, metadata.group_info)
     }
 
-    #[must_use]
-    pub unsafe fn group_range(
+    /// Returns the groups whose lengths bound the dense range of a query.
+    pub fn group_bounds(
         &self,
         include: &QueryGroupInfo,
         exclude: &QueryGroupInfo,
-    ) -> Option<Range<usize>> {
+    ) -> Result<GroupBounds, DenseRejection> {
         type Info = QueryGroupInfo;
 
         match (include, exclude) {
-            (Info::One(view), Info::Empty) => Some(0..view.len),
-            (Info::Many(include), Info::Empty) => self.include_group_range(*include),
+            (Info::One(_), Info::Empty) => Ok(GroupBounds::View),
+            (Info::Empty, _) => Err(DenseRejection::Unconstrained),
+            (Info::Many(include), Info::Empty) => self.include_group_bounds(*include),
             (include, exclude) => {
-                let include = include.group_info()?;
-                let exclude = exclude.group_info()?;
-                self.exclude_group_range(include, exclude)
+                let include = include.group_info().ok_or(DenseRejection::NoGroup)?;
+                let exclude = exclude.group_info().ok_or(DenseRejection::NoGroup)?;
+                self.exclude_group_bounds(include, exclude)
             }
         }
     }
 
-    #[must_use]
-    unsafe fn include_group_range(&self, include: GroupInfo) -> Option<Range<usize>> {
-        let group = unsafe {
-            self.groups
-                .get_unchecked(usize::from(include.group_end) - 1)
-        };
+    /// Returns the dense range of a query from its group `bounds`. `view_len`
+    /// is the length of the only view of the query, if `bounds` is
+    /// [`GroupBounds::View`].
+    ///
+    /// # Safety
+    ///
+    /// `bounds` must have been returned by [`group_bounds`](Self::group_bounds)
+    /// on this storage, in its current generation.
+    pub unsafe fn group_range(
+        &self,
+        bounds: GroupBounds,
+        view_len: usize,
+    ) -> Result<Range<usize>, DenseRejection> {
+        match bounds {
+            GroupBounds::View => Ok(0..view_len),
+            // Group ranges are incomplete while grouping is deferred.
+            _ if self.deferred.is_some() => Err(DenseRejection::GroupingDeferred),
+            GroupBounds::Include(group) => Ok(0..self.groups.get_unchecked(group).len),
+            GroupBounds::Exclude { child, parent } => {
+                let child = self.groups.get_unchecked(child).len;
+                let parent = self.groups.get_unchecked(parent).len;
+                Ok(child..parent)
+            }
+        }
+    }
+
+    fn include_group_bounds(&self, include: GroupInfo) -> Result<GroupBounds, DenseRejection> {
+        let group_index = usize::from(include.group_end) - 1;
+        let group = &self.groups[group_index];
 
         let mask = QueryMask {
             include: include.storage_mask.into(),
             exclude: StorageMask::EMPTY,
         };
 
-        (mask == group.metadata.include_mask).then_some(0..group.len)
+        if mask != group.metadata.include_mask {
+            return Err(DenseRejection::MismatchedGroup);
+        }
+
+        Ok(GroupBounds::Include(group_index))
     }
 
-    #[must_use]
-    unsafe fn exclude_group_range(
+    fn exclude_group_bounds(
         &self,
         include: GroupInfo,
         exclude: GroupInfo,
-    ) -> Option<Range<usize>> {
+    ) -> Result<GroupBounds, DenseRejection> {
         if include.group_start != exclude.group_start {
-            return None;
+            return Err(DenseRejection::NoGroup);
         }
 
-        let group_end = cmp::max(include.group_end, exclude.group_end);
-        let child_group = unsafe { self.groups.get_unchecked(usize::from(group_end) - 1) };
+        let group_index = usize::from(cmp::max(include.group_end, exclude.group_end)) - 1;
+        let child_group = &self.groups[group_index];
 
         let mask = QueryMask {
             include: include.storage_mask.into(),
@@ -284,11 +765,102 @@ impl ComponentStorage {
         };
 
         if mask != child_group.metadata.exclude_mask {
+            return Err(DenseRejection::ExcludeNotAtGroupBoundary);
+        }
+
+        Ok(GroupBounds::Exclude {
+            child: group_index,
+            parent: group_index - 1,
+        })
+    }
+
+    /// Finds the non-owning groups whose component types are all in the
+    /// sorted and deduplicated `required` list.
+    #[must_use]
+    pub fn non_owning_match(&self, required: &[ComponentData]) -> NonOwningMatch {
+        let mut result = NonOwningMatch::default();
+
+        for (i, group) in self.non_owning_groups.iter().enumerate() {
+            let is_subset = group
+                .components
+                .iter()
+                .all(|c| required.binary_search(c).is_ok());
+
+            if is_subset {
+                result.subset_mask |= GroupMask::single(i);
+
+                if group.components.len() == required.len() {
+                    result.exact = Some(i);
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Returns the shortest entity list of the non-owning groups in
+    /// `group_mask`, if any.
+    #[must_use]
+    pub fn non_owning_entities(&self, group_mask: GroupMask) -> Option<&[Entity]> {
+        if self.deferred.is_some() {
+            return None;
+        }
+
+        group_mask
+            .iter_bit_indexes()
+            .map(|i| unsafe {
+                self.non_owning_groups
+                    .get_unchecked(i as usize)
+                    .entities
+                    .as_slice()
+            })
+            .min_by_key(|entities| entities.len())
+    }
+
+    /// Returns the entity list of the non-owning group at `group_index`.
+    #[must_use]
+    pub fn non_owning_group_entities(&self, group_index: usize) -> Option<&[Entity]> {
+        if self.deferred.is_some() {
             return None;
         }
 
-        let parent_group = unsafe { self.groups.get_unchecked(usize::from(group_end) - 2) };
-        Some(child_group.len..parent_group.len)
+        self.non_owning_groups
+            .get(group_index)
+            .map(|group| group.entities.as_slice())
+    }
+
+    /// Empties all groups that contain the component type `T`.
+    ///
+    /// Returns the sparse set of `T`.
+    #[must_use]
+    fn ungroup_storage<T>(&mut self) -> &mut ComponentSparseSet
+    where
+        T: Component,
+    {
+        let Some(metadata) = self.metadata.get(&TypeId::of::<T>()) else {
+            panic_missing_comp::<T>();
+        };
+
+        for group_index in metadata.delete_mask.iter_bit_indexes() {
+            let group = unsafe { self.groups.get_unchecked_mut(group_index as usize) };
+            group.record_clear(&mut self.components);
+            group.len = 0;
+        }
+
+        for group_index in metadata.non_owning_mask.iter_bit_indexes() {
+            unsafe {
+                self.non_owning_groups
+                    .get_unchecked_mut(group_index as usize)
+                    .entities
+                    .clear();
+            }
+        }
+
+        unsafe {
+            self.components
+                .get_unchecked_mut(metadata.storage_index)
+                .get_mut()
+        }
     }
 
     #[must_use]
@@ -299,7 +871,7 @@ impl ComponentStorage {
             .map(|(type_id, metadata)| {
                 let sparse_set = mem::replace(
                     self.components[metadata.storage_index].get_mut(),
-                    ComponentSparseSet::new::<()>(),
+                    ComponentSparseSet::new::<()>(SharedAllocator::Global),
                 );
 
                 (type_id, sparse_set)
@@ -308,16 +880,30 @@ impl ComponentStorage {
 
         self.groups.clear();
         self.metadata.clear();
+        self.components.clear();
 
         sparse_sets
     }
 }
 
+/// Groups whose lengths bound the dense range of a query.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum GroupBounds {
+    /// The range covers all components of the only view of the query.
+    View,
+    /// The range covers the entities of the group at the given index.
+    Include(usize),
+    /// The range covers the entities of the `parent` group that are not in
+    /// the `child` group.
+    Exclude { child: usize, parent: usize },
+}
+
 #[derive(Clone, Copy, Debug)]
 pub(crate) struct ComponentMetadata {
     pub storage_index: usize,
     pub insert_mask: GroupMask,
     pub delete_mask: GroupMask,
+    pub non_owning_mask: GroupMask,
     pub group_info: Option<GroupInfo>,
 }
 
@@ -326,3 +912,48 @@ pub(crate) struct ComponentMetadata {
 pub(crate) fn panic_missing_comp<T>() -> ! {
     panic!("Component '{}' was not registered", any::type_name::<T>());
 }
+
+#[cold]
+#[inline(never)]
+fn panic_missing_group(components: &[ComponentData]) -> ! {
+    panic!("Group was not found in the layout: {components:#?}");
+}
+
+#[cold]
+#[inline(never)]
+fn panic_unsorted_group(components: &[ComponentData]) -> ! {
+    panic!("Group is not sorted: {components:#?}");
+}
+
+/// Returns the index of the group in `old_layout` with the same component
+/// types as each group of `family`, if any.
+#[must_use]
+fn kept_group_indexes(family: &GroupFamily, old_layout: &GroupLayout) -> Vec<Option<usize>> {
+    family
+        .arities()
+        .iter()
+        .map(|&arity| {
+            let components = &family.components()[..arity];
+
+            old_layout
+                .families()
+                .iter()
+                .flat_map(|old_family| {
+                    old_family
+                        .arities()
+                        .iter()
+                        .map(|&old_arity| &old_family.components()[..old_arity])
+                })
+                .position(|old_components| {
+                    old_components.len() == components.len()
+                        && components.iter().all(|c| old_components.contains(c))
+                })
+        })
+        .collect()
+}
+
+#[cold]
+#[inline(never)]
+pub(crate) fn panic_missing_comp_dyn(component: ComponentData) -> ! {
+    panic!("Component '{}' was not registered", component.type_name());
+}
@@ -0,0 +1,125 @@
+use crate::component::{Component, ComponentData};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::any::{Any, TypeId};
+use core::{fmt, mem};
+
+pub(crate) type BoxedComponent = Box<dyn Any + Send + Sync>;
+
+/// Type-erased collection of components that can be added to an entity.
+#[derive(Default)]
+pub struct EntityComponents {
+    components: Vec<(ComponentData, BoxedComponent)>,
+}
+
+impl EntityComponents {
+    /// Creates an empty collection of components.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            components: Vec::new(),
+        }
+    }
+
+    /// Adds the `component` to the collection.
+    ///
+    /// Returns the previous component of the same type, if any.
+    pub fn insert<T>(&mut self, component: T) -> Option<T>
+    where
+        T: Component,
+    {
+        if let Some(old_component) = self.get_mut::<T>() {
+            return Some(mem::replace(old_component, component));
+        }
+
+        self.components
+            .push((ComponentData::new::<T>(), Box::new(component)));
+
+        None
+    }
+
+    /// Removes the component of type `T` from the collection, if any.
+    pub fn remove<T>(&mut self) -> Option<T>
+    where
+        T: Component,
+    {
+        let index = self.position(TypeId::of::<T>())?;
+        let (_, component) = self.components.swap_remove(index);
+        component.downcast::<T>().ok().map(|component| *component)
+    }
+
+    /// Returns a reference to the component of type `T`, if any.
+    #[must_use]
+    pub fn get<T>(&self) -> Option<&T>
+    where
+        T: Component,
+    {
+        let index = self.position(TypeId::of::<T>())?;
+        self.components[index].1.downcast_ref::<T>()
+    }
+
+    /// Returns a mutable reference to the component of type `T`, if any.
+    #[must_use]
+    pub fn get_mut<T>(&mut self) -> Option<&mut T>
+    where
+        T: Component,
+    {
+        let index = self.position(TypeId::of::<T>())?;
+        self.components[index].1.downcast_mut::<T>()
+    }
+
+    /// Returns whether the collection contains a component of type `T`.
+    #[must_use]
+    pub fn contains<T>(&self) -> bool
+    where
+        T: Component,
+    {
+        self.position(TypeId::of::<T>()).is_some()
+    }
+
+    /// Returns an iterator over the types of the components in the collection.
+    #[must_use]
+    pub fn types(&self) -> impl ExactSizeIterator<Item = ComponentData> + '_ {
+        self.components.iter().map(|(data, _)| *data)
+    }
+
+    /// Returns the number of components in the collection.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    /// Returns whether the collection is empty.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+
+    #[inline]
+    pub(crate) fn push(&mut self, data: ComponentData, component: BoxedComponent) {
+        self.components.push((data, component));
+    }
+
+    #[inline]
+    pub(crate) fn into_inner(self) -> Vec<(ComponentData, BoxedComponent)> {
+        self.components
+    }
+
+    #[must_use]
+    fn position(&self, type_id: TypeId) -> Option<usize> {
+        self.components
+            .iter()
+            .position(|(data, _)| data.type_id() == type_id)
+    }
+}
+
+impl fmt::Debug for EntityComponents {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.components.iter().map(|(data, _)| data.type_name()))
+            .finish()
+    }
+}
@@ -1,12 +1,33 @@
-use crate::component::{ComponentSparseSet, GroupMask, QueryMask};
+use crate::component::{ComponentSparseSet, GroupEvent, GroupMask, QueryMask};
 use crate::entity::Entity;
+use alloc::vec::Vec;
 use atomic_refcell::AtomicRefCell;
 use core::ops::Range;
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub(crate) struct Group {
     pub metadata: GroupMetadata,
     pub len: usize,
+    /// Membership changes recorded while the group is tracked.
+    pub events: Option<Vec<GroupEvent>>,
+}
+
+impl Group {
+    #[inline]
+    pub fn record(&mut self, event: GroupEvent) {
+        if let Some(events) = &mut self.events {
+            events.push(event);
+        }
+    }
+
+    /// Records that all grouped entities left the group. Must be called
+    /// before the group is emptied.
+    pub fn record_clear(&mut self, components: &mut [AtomicRefCell<ComponentSparseSet>]) {
+        if let Some(events) = &mut self.events {
+            let entities = components[self.metadata.storage_start].get_mut().entities();
+            events.extend(entities[..self.len].iter().copied().map(GroupEvent::Left));
+        }
+    }
 }
 
 /// Example:
@@ -75,12 +96,63 @@ pub(crate) unsafe fn group(
                     &mut group.len,
                     entity,
                 );
+
+                group.record(GroupEvent::Entered(entity));
             }
             GroupStatus::Grouped => (),
         }
     }
 }
 
+/// Groups all complete entities in one pass over the storages of each group in
+/// `group_mask`. Already grouped entities are not moved.
+pub(crate) unsafe fn group_all(
+    components: &mut [AtomicRefCell<ComponentSparseSet>],
+    groups: &mut [Group],
+    group_mask: GroupMask,
+) {
+    for group_index in group_mask.iter_bit_indexes() {
+        let group_index = group_index as usize;
+        let metadata = groups.get_unchecked(group_index).metadata;
+
+        // Entities can only join a nested group if they are part of its parent.
+        let scan_end = if metadata.new_storage_start == metadata.storage_start {
+            components
+                .get_unchecked_mut(metadata.storage_start)
+                .get_mut()
+                .len()
+        } else {
+            groups.get_unchecked(group_index - 1).len
+        };
+
+        let group = groups.get_unchecked_mut(group_index);
+
+        for i in group.len..scan_end {
+            let entity = *components
+                .get_unchecked_mut(metadata.storage_start)
+                .get_mut()
+                .entities()
+                .get_unchecked(i);
+
+            let status = get_group_status(
+                &mut components[metadata.new_storage_range()],
+                group.len,
+                entity,
+            );
+
+            if status == GroupStatus::Ungrouped {
+                group_components(
+                    &mut components[metadata.storage_range()],
+                    &mut group.len,
+                    entity,
+                );
+
+                group.record(GroupEvent::Entered(entity));
+            }
+        }
+    }
+}
+
 pub(crate) unsafe fn ungroup(
     components: &mut [AtomicRefCell<ComponentSparseSet>],
     groups: &mut [Group],
@@ -102,6 +174,8 @@ pub(crate) unsafe fn ungroup(
                 &mut group.len,
                 entity,
             );
+
+            group.record(GroupEvent::Left(entity));
         }
     }
 }
@@ -124,6 +198,8 @@ pub(crate) unsafe fn ungroup_all(
                 &mut group.len,
                 entity,
             );
+
+            group.record(GroupEvent::Left(entity));
         }
     }
 }
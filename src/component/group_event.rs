@@ -0,0 +1,21 @@
+use crate::entity::Entity;
+
+/// Change in the membership of a tracked component group.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum GroupEvent {
+    /// The entity now has all the components of the group.
+    Entered(Entity),
+    /// The entity no longer has all the components of the group.
+    Left(Entity),
+}
+
+impl GroupEvent {
+    /// Returns the entity whose membership changed.
+    #[inline]
+    #[must_use]
+    pub const fn entity(&self) -> Entity {
+        match self {
+            Self::Entered(entity) | Self::Left(entity) => *entity,
+        }
+    }
+}
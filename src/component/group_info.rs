@@ -33,6 +33,20 @@ impl QueryGroupInfo {
         Some(query)
     }
 
+    /// Tries to combine the group infos of the components excluded by two
+    /// queries.
+    ///
+    /// Matched entities lack at least one of the components of an exclusion,
+    /// so two non-empty exclusions can't be bounded by one group.
+    #[inline]
+    #[must_use]
+    pub fn add_exclusion(&self, other: &Self) -> Option<Self> {
+        match (self, other) {
+            (exclusion, Self::Empty) | (Self::Empty, exclusion) => Some(*exclusion),
+            _ => None,
+        }
+    }
+
     /// Tries to add a view to the current group info.
     ///
     /// Returns the new group info if the operands were compatible.
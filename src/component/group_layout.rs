@@ -1,4 +1,4 @@
-use crate::component::{Component, ComponentData};
+use crate::component::{Component, ComponentData, GroupSort};
 use alloc::vec;
 use alloc::vec::Vec;
 use core::cmp::Ordering;
@@ -7,17 +7,29 @@ use core::cmp::Ordering;
 pub const MIN_GROUP_ARITY: usize = 2;
 
 /// Maximum number of component types that can form a group.
+#[cfg(not(feature = "wide-groups"))]
 pub const MAX_GROUP_ARITY: usize = 16;
 
+/// Maximum number of component types that can form a group.
+#[cfg(feature = "wide-groups")]
+pub const MAX_GROUP_ARITY: usize = 32;
+
 /// Maximum number of groups that can be set on a
 /// [`World`](crate::world::World).
+#[cfg(not(feature = "wide-groups"))]
 pub const MAX_GROUP_COUNT: usize = 64;
 
+/// Maximum number of groups that can be set on a
+/// [`World`](crate::world::World).
+#[cfg(feature = "wide-groups")]
+pub const MAX_GROUP_COUNT: usize = 128;
+
 /// Describes the layout of the component groups that can be set on a
 /// [`World`](crate::world::World).
 #[derive(Clone, Default, Debug)]
 pub struct GroupLayout {
     families: Vec<GroupFamily>,
+    non_owning_groups: Vec<Vec<ComponentData>>,
 }
 
 impl GroupLayout {
@@ -58,6 +70,208 @@ impl GroupLayout {
                 "Groups families may not have any component types in common",
             );
         }
+
+        let group_count = self.families.iter().map(|f| f.arities.len()).sum::<usize>();
+
+        assert!(
+            group_count <= MAX_GROUP_COUNT,
+            "Layouts must have at most {MAX_GROUP_COUNT} groups",
+        );
+    }
+
+    /// Returns whether [`add_group_dyn`](Self::add_group_dyn) would succeed
+    /// for the given components without panicking.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub(crate) fn can_add_group_dyn(&self, components: &[ComponentData]) -> bool {
+        let mut components = Vec::from(components);
+        components.sort_unstable();
+        components.dedup();
+
+        if components.len() <= 1 {
+            return true;
+        }
+
+        if components.len() > MAX_GROUP_ARITY {
+            return false;
+        }
+
+        let mut overlaps = 0;
+        let mut is_new = true;
+
+        for family in &self.families {
+            match family.find_slot(&components) {
+                GroupSlot::Disjoint => continue,
+                GroupSlot::Existing => is_new = false,
+                GroupSlot::Incompatible(_) => return false,
+                GroupSlot::Insert(_) => (),
+                GroupSlot::Append => {
+                    if family.sort.is_some() {
+                        return false;
+                    }
+                }
+            }
+
+            overlaps += 1;
+        }
+
+        let group_count = self.families.iter().map(|f| f.arities.len()).sum::<usize>();
+        overlaps <= 1 && (!is_new || group_count < MAX_GROUP_COUNT)
+    }
+
+    /// Adds a new group to the layout, kept sorted by its components of type
+    /// `T`.
+    ///
+    /// Only the group with the most component types in a family can be sorted.
+    /// The order is restored on demand by
+    /// [`World::sort_group`](crate::world::World::sort_group).
+    pub fn add_sorted_group<G, T>(&mut self, compare: fn(&T, &T) -> Ordering)
+    where
+        G: GroupDescriptor,
+        T: Component,
+    {
+        self.add_sorted_group_dyn(G::COMPONENTS, GroupSort::new(compare));
+    }
+
+    /// Adds a new group to the layout, kept sorted by the given comparator.
+    ///
+    /// Only the group with the most component types in a family can be sorted.
+    /// The order is restored on demand by
+    /// [`World::sort_group`](crate::world::World::sort_group).
+    pub fn add_sorted_group_dyn(&mut self, components: &[ComponentData], sort: GroupSort) {
+        assert!(
+            components.contains(&sort.component()),
+            "Sorted groups must contain the component type used for sorting",
+        );
+
+        self.add_group_dyn(components);
+
+        let Some(family) = self
+            .families
+            .iter_mut()
+            .find(|f| f.is_largest_group(components))
+        else {
+            panic_unsortable_group(components);
+        };
+
+        family.sort = Some(sort);
+    }
+
+    /// Returns the comparator of the given sorted group, if any.
+    #[must_use]
+    pub(crate) fn group_sort(&self, components: &[ComponentData]) -> Option<GroupSort> {
+        self.families
+            .iter()
+            .find(|f| f.is_largest_group(components))?
+            .sort
+            .clone()
+    }
+
+    /// Removes a group from the layout.
+    ///
+    /// Returns whether the group was present in the layout.
+    pub fn remove_group<G>(&mut self) -> bool
+    where
+        G: GroupDescriptor,
+    {
+        self.remove_group_dyn(G::COMPONENTS)
+    }
+
+    /// Removes a group from the layout.
+    ///
+    /// Returns whether the group was present in the layout.
+    pub fn remove_group_dyn(&mut self, components: &[ComponentData]) -> bool {
+        let mut components = Vec::from(components);
+        components.sort_unstable();
+        components.dedup();
+
+        let Some(family_index) = self
+            .families
+            .iter_mut()
+            .position(|f| f.try_remove_group(&components))
+        else {
+            return false;
+        };
+
+        if self.families[family_index].arities.is_empty() {
+            self.families.remove(family_index);
+        }
+
+        true
+    }
+
+    /// Adds a new non-owning group to the layout.
+    ///
+    /// Non-owning groups keep a packed list of the entities that have all of
+    /// their components, without reordering the component storages. Unlike
+    /// owning groups, they may have component types in common with any other
+    /// group.
+    pub fn add_non_owning_group<G>(&mut self)
+    where
+        G: GroupDescriptor,
+    {
+        self.add_non_owning_group_dyn(G::COMPONENTS);
+    }
+
+    /// Adds a new non-owning group to the layout.
+    ///
+    /// Non-owning groups keep a packed list of the entities that have all of
+    /// their components, without reordering the component storages. Unlike
+    /// owning groups, they may have component types in common with any other
+    /// group.
+    pub fn add_non_owning_group_dyn(&mut self, components: &[ComponentData]) {
+        let mut components = Vec::from(components);
+        components.sort_unstable();
+        components.dedup();
+
+        if components.len() <= 1 || self.non_owning_groups.contains(&components) {
+            return;
+        }
+
+        assert!(
+            components.len() <= MAX_GROUP_ARITY,
+            "Groups must have at most {MAX_GROUP_ARITY} component types",
+        );
+
+        assert!(
+            self.non_owning_groups.len() < MAX_GROUP_COUNT,
+            "Layouts must have at most {MAX_GROUP_COUNT} non-owning groups",
+        );
+
+        self.non_owning_groups.push(components);
+    }
+
+    /// Removes a non-owning group from the layout.
+    ///
+    /// Returns whether the group was present in the layout.
+    pub fn remove_non_owning_group<G>(&mut self) -> bool
+    where
+        G: GroupDescriptor,
+    {
+        self.remove_non_owning_group_dyn(G::COMPONENTS)
+    }
+
+    /// Removes a non-owning group from the layout.
+    ///
+    /// Returns whether the group was present in the layout.
+    pub fn remove_non_owning_group_dyn(&mut self, components: &[ComponentData]) -> bool {
+        let mut components = Vec::from(components);
+        components.sort_unstable();
+        components.dedup();
+
+        let Some(index) = self.non_owning_groups.iter().position(|g| *g == components) else {
+            return false;
+        };
+
+        self.non_owning_groups.remove(index);
+        true
+    }
+
+    /// Returns the component types of the non-owning groups of this layout.
+    #[inline]
+    #[must_use]
+    pub(crate) fn non_owning_groups(&self) -> &[Vec<ComponentData>] {
+        &self.non_owning_groups
     }
 
     /// Returns the group families of this layout.
@@ -69,10 +283,11 @@ impl GroupLayout {
 }
 
 /// Describes a set of related component groups.
-#[derive(Clone, Debug)]
+#[derive(Clone, PartialEq, Eq, Debug)]
 pub(crate) struct GroupFamily {
     components: Vec<ComponentData>,
     arities: Vec<usize>,
+    sort: Option<GroupSort>,
 }
 
 impl GroupFamily {
@@ -80,9 +295,16 @@ impl GroupFamily {
         Self {
             arities: vec![components.len()],
             components,
+            sort: None,
         }
     }
 
+    #[must_use]
+    fn is_largest_group(&self, components: &[ComponentData]) -> bool {
+        self.components.iter().all(|c| components.contains(c))
+            && components.iter().all(|c| self.components.contains(c))
+    }
+
     /// Returns the components that are part of the group family.
     #[inline]
     #[must_use]
@@ -97,16 +319,68 @@ impl GroupFamily {
         &self.arities
     }
 
+    #[must_use]
+    fn try_remove_group(&mut self, components: &[ComponentData]) -> bool {
+        let Some(index) = self.arities.iter().position(|&arity| {
+            arity == components.len()
+                && self.components[..arity]
+                    .iter()
+                    .all(|c| components.contains(c))
+        }) else {
+            return false;
+        };
+
+        if index + 1 == self.arities.len() {
+            self.sort = None;
+        }
+
+        self.arities.remove(index);
+
+        // Drop the components that were only part of the removed group.
+        let arity = self.arities.last().copied().unwrap_or(0);
+        self.components.truncate(arity);
+        true
+    }
+
     #[must_use]
     fn try_add_group(&mut self, components: &[ComponentData]) -> bool {
+        match self.find_slot(components) {
+            GroupSlot::Disjoint => false,
+            GroupSlot::Existing => true,
+            GroupSlot::Incompatible(arity) => {
+                panic_incompatible_groups(components, &self.components[..arity]);
+            }
+            GroupSlot::Insert(index) => {
+                let next_arity = self.arities[index];
+                self.components[..next_arity].sort_by_cached_key(|c| components.contains(c));
+                self.arities.insert(index, components.len());
+                true
+            }
+            GroupSlot::Append => {
+                if self.sort.is_some() {
+                    panic_unsortable_group(&self.components);
+                }
+
+                for component in components {
+                    if !self.components.contains(component) {
+                        self.components.push(*component);
+                    }
+                }
+
+                self.arities.push(components.len());
+                true
+            }
+        }
+    }
+
+    /// Finds where a group made of `components` fits in the family.
+    #[must_use]
+    fn find_slot(&self, components: &[ComponentData]) -> GroupSlot {
         // Check if groups are disjoint.
         if self.components.iter().all(|c| !components.contains(c)) {
-            return false;
+            return GroupSlot::Disjoint;
         }
 
-        // Find insertion index for new group.
-        let mut index = Option::<usize>::None;
-
         for (i, &arity) in self.arities.iter().enumerate() {
             let prev_arity = i.checked_sub(1).map_or(0, |i| self.arities[i]);
 
@@ -117,7 +391,7 @@ impl GroupFamily {
                         .all(|c| components.contains(c));
 
                     if !is_subset {
-                        panic_incompatible_groups(components, &self.components[..arity]);
+                        return GroupSlot::Incompatible(arity);
                     }
                 }
                 Ordering::Equal => {
@@ -126,10 +400,10 @@ impl GroupFamily {
                         .all(|c| components.contains(c));
 
                     if !is_equal {
-                        panic_incompatible_groups(components, &self.components[..arity]);
+                        return GroupSlot::Incompatible(arity);
                     }
 
-                    return true;
+                    return GroupSlot::Existing;
                 }
                 Ordering::Greater => {
                     let is_superset = self.components[prev_arity..arity]
@@ -137,34 +411,33 @@ impl GroupFamily {
                         .all(|c| !components.contains(c));
 
                     if !is_superset {
-                        panic_incompatible_groups(components, &self.components[..arity]);
+                        return GroupSlot::Incompatible(arity);
                     }
 
-                    index = Some(i);
-                    break;
-                }
-            }
-        }
-
-        // Insert new group.
-        if let Some(index) = index {
-            let next_arity = self.arities[index];
-            self.components[..next_arity].sort_by_cached_key(|c| components.contains(c));
-            self.arities.insert(index, components.len());
-        } else {
-            for component in components {
-                if !self.components.contains(component) {
-                    self.components.push(*component);
+                    return GroupSlot::Insert(i);
                 }
             }
-
-            self.arities.push(components.len());
         }
 
-        true
+        GroupSlot::Append
     }
 }
 
+/// Position of a new group relative to an existing group family.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum GroupSlot {
+    /// The group has no component types in common with the family.
+    Disjoint,
+    /// The group is already part of the family.
+    Existing,
+    /// The group conflicts with the family's group of the given arity.
+    Incompatible(usize),
+    /// The group must be inserted before the group at the given index.
+    Insert(usize),
+    /// The group contains all groups of the family.
+    Append,
+}
+
 /// Helper trait for creating groups in a [`GroupLayout`].
 pub trait GroupDescriptor {
     /// Slice containing the component data of the components present in the
@@ -172,6 +445,14 @@ pub trait GroupDescriptor {
     const COMPONENTS: &'static [ComponentData];
 }
 
+#[cold]
+#[inline(never)]
+fn panic_unsortable_group(group: &[ComponentData]) -> ! {
+    panic!(
+        "Only the group with the most component types in a family can be sorted:\n -> {group:#?}"
+    )
+}
+
 #[cold]
 #[inline(never)]
 fn panic_incompatible_groups(new_group: &[ComponentData], old_group: &[ComponentData]) -> ! {
@@ -206,3 +487,51 @@ impl_group_descriptor!(A, B, C, D, E, F, G, H, I, J, K, L, M);
 impl_group_descriptor!(A, B, C, D, E, F, G, H, I, J, K, L, M, N);
 impl_group_descriptor!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O);
 impl_group_descriptor!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P);
+#[cfg(feature = "wide-groups")]
+impl_group_descriptor!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q);
+#[cfg(feature = "wide-groups")]
+impl_group_descriptor!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R);
+#[cfg(feature = "wide-groups")]
+impl_group_descriptor!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S);
+#[cfg(feature = "wide-groups")]
+impl_group_descriptor!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T);
+#[cfg(feature = "wide-groups")]
+impl_group_descriptor!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U);
+#[cfg(feature = "wide-groups")]
+impl_group_descriptor!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V);
+#[cfg(feature = "wide-groups")]
+impl_group_descriptor!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W);
+#[cfg(feature = "wide-groups")]
+impl_group_descriptor!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X);
+#[cfg(feature = "wide-groups")]
+impl_group_descriptor!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y);
+#[cfg(feature = "wide-groups")]
+impl_group_descriptor!(
+    A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z
+);
+#[cfg(feature = "wide-groups")]
+impl_group_descriptor!(
+    A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA
+);
+#[cfg(feature = "wide-groups")]
+impl_group_descriptor!(
+    A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB
+);
+#[cfg(feature = "wide-groups")]
+impl_group_descriptor!(
+    A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB, AC
+);
+#[cfg(feature = "wide-groups")]
+impl_group_descriptor!(
+    A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB, AC, AD
+);
+#[cfg(feature = "wide-groups")]
+impl_group_descriptor!(
+    A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB, AC, AD,
+    AE
+);
+#[cfg(feature = "wide-groups")]
+impl_group_descriptor!(
+    A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, AA, AB, AC, AD,
+    AE, AF
+);
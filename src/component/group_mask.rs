@@ -2,8 +2,14 @@ use crate::component::{StorageMask, MAX_GROUP_COUNT};
 use core::fmt;
 use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign};
 
+#[cfg(not(feature = "wide-groups"))]
+pub(crate) type GroupMaskBits = u64;
+
+#[cfg(feature = "wide-groups")]
+pub(crate) type GroupMaskBits = u128;
+
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
-pub(crate) struct GroupMask(pub u64);
+pub(crate) struct GroupMask(pub GroupMaskBits);
 
 impl GroupMask {
     pub const EMPTY: Self = Self(0);
@@ -11,27 +17,43 @@ impl GroupMask {
     #[inline]
     #[must_use]
     pub const fn from_to(from: usize, to: usize) -> Self {
-        assert!(from < MAX_GROUP_COUNT);
-        assert!(to < MAX_GROUP_COUNT);
         assert!(from <= to);
+        assert!(to <= MAX_GROUP_COUNT);
+
+        Self(Self::ones(to - from) << from)
+    }
 
-        Self(((1 << (to - from)) - 1) << from)
+    #[inline]
+    #[must_use]
+    pub const fn single(index: usize) -> Self {
+        assert!(index < MAX_GROUP_COUNT);
+        Self(1 << index)
     }
 
     #[inline]
     #[must_use]
     pub const fn skip_from_to(from: usize, to: usize) -> Self {
-        assert!(from < MAX_GROUP_COUNT);
-        assert!(to < MAX_GROUP_COUNT);
         assert!(from <= to);
+        assert!(to <= MAX_GROUP_COUNT);
 
-        Self(!(((1 << (to - from)) - 1) << from))
+        Self(!(Self::ones(to - from) << from))
     }
 
     #[inline]
     pub const fn iter_bit_indexes(self) -> BitIndexIter {
         BitIndexIter(self.0)
     }
+
+    /// Returns a mask with the lowest `count` bits set.
+    #[inline]
+    #[must_use]
+    const fn ones(count: usize) -> GroupMaskBits {
+        if count == 0 {
+            0
+        } else {
+            GroupMaskBits::MAX >> (GroupMaskBits::BITS as usize - count)
+        }
+    }
 }
 
 impl BitAnd for GroupMask {
@@ -68,7 +90,7 @@ impl BitOrAssign for GroupMask {
 
 impl fmt::Debug for GroupMask {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:0>64b}", self.0)
+        write!(f, "{:0>1$b}", self.0, GroupMaskBits::BITS as usize)
     }
 }
 
@@ -101,7 +123,7 @@ impl QueryMask {
 
 #[must_use]
 #[derive(Clone, Debug)]
-pub(crate) struct BitIndexIter(pub u64);
+pub(crate) struct BitIndexIter(pub GroupMaskBits);
 
 impl Iterator for BitIndexIter {
     type Item = u32;
@@ -125,7 +147,7 @@ impl DoubleEndedIterator for BitIndexIter {
             return None;
         }
 
-        let index = 63 - self.0.leading_zeros();
+        let index = self.0.ilog2();
         self.0 &= !(1 << index);
         Some(index)
     }
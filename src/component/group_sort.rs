@@ -0,0 +1,89 @@
+use crate::component::{Component, ComponentData, ComponentSparseSet};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+
+/// Type-erased comparator used to sort the components of a group.
+///
+/// Two comparators are equal only if one is a clone of the other, so layouts
+/// cloned from each other keep their sorted groups.
+#[derive(Clone)]
+pub struct GroupSort {
+    component: ComponentData,
+    sort: Arc<dyn SortIndexes>,
+}
+
+impl GroupSort {
+    /// Creates a new comparator that sorts a group by its components of type
+    /// `T`.
+    #[must_use]
+    pub fn new<T>(compare: fn(&T, &T) -> Ordering) -> Self
+    where
+        T: Component,
+    {
+        Self {
+            component: ComponentData::new::<T>(),
+            sort: Arc::new(TypedSort { compare }),
+        }
+    }
+
+    /// Returns the component type used for sorting.
+    #[inline]
+    #[must_use]
+    pub fn component(&self) -> ComponentData {
+        self.component
+    }
+
+    /// Returns the dense indexes of the first `len` components in `sparse_set`
+    /// in sorted order.
+    ///
+    /// The `sparse_set` must contain components of the sorted type.
+    #[must_use]
+    pub(crate) unsafe fn sort_indexes(
+        &self,
+        sparse_set: &ComponentSparseSet,
+        len: usize,
+    ) -> Vec<usize> {
+        self.sort.sort_indexes(sparse_set, len)
+    }
+}
+
+impl PartialEq for GroupSort {
+    fn eq(&self, other: &Self) -> bool {
+        self.component == other.component && Arc::ptr_eq(&self.sort, &other.sort)
+    }
+}
+
+impl Eq for GroupSort {
+    // Empty
+}
+
+impl fmt::Debug for GroupSort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(stringify!(GroupSort))
+            .field("component", &self.component)
+            .finish_non_exhaustive()
+    }
+}
+
+trait SortIndexes: Send + Sync {
+    unsafe fn sort_indexes(&self, sparse_set: &ComponentSparseSet, len: usize) -> Vec<usize>;
+}
+
+struct TypedSort<T> {
+    compare: fn(&T, &T) -> Ordering,
+}
+
+impl<T> SortIndexes for TypedSort<T>
+where
+    T: Component,
+{
+    unsafe fn sort_indexes(&self, sparse_set: &ComponentSparseSet, len: usize) -> Vec<usize> {
+        let components = sparse_set.as_slice::<T>();
+
+        let mut indexes = (0..len).collect::<Vec<_>>();
+        indexes.sort_by(|&a, &b| (self.compare)(&components[a], &components[b]));
+        indexes
+    }
+}
@@ -0,0 +1,61 @@
+use crate::component::ComponentData;
+
+/// Read-only description of a component group set on a
+/// [`World`](crate::world::World).
+#[derive(Clone, Copy, Debug)]
+pub struct GroupSummary<'a> {
+    pub(crate) index: usize,
+    pub(crate) family: usize,
+    pub(crate) components: &'a [ComponentData],
+    pub(crate) len: usize,
+}
+
+impl<'a> GroupSummary<'a> {
+    /// Returns the index of the group in the world's layout.
+    #[inline]
+    #[must_use]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the index of the family the group belongs to.
+    #[inline]
+    #[must_use]
+    pub fn family(&self) -> usize {
+        self.family
+    }
+
+    /// Returns the component types owned by the group.
+    #[inline]
+    #[must_use]
+    pub fn components(&self) -> &'a [ComponentData] {
+        self.components
+    }
+
+    /// Returns the names of the component types owned by the group.
+    #[inline]
+    pub fn component_names(&self) -> impl ExactSizeIterator<Item = &'static str> + 'a {
+        self.components.iter().map(ComponentData::type_name)
+    }
+
+    /// Returns the number of component types owned by the group.
+    #[inline]
+    #[must_use]
+    pub fn arity(&self) -> usize {
+        self.components.len()
+    }
+
+    /// Returns the number of entities currently in the group.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the group contains no entities.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
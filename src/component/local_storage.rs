@@ -0,0 +1,122 @@
+use crate::allocator::SharedAllocator;
+use crate::component::{ComponentSparseSet, FxHashMap, View, ViewMut};
+use crate::entity::Entity;
+use alloc::vec::Vec;
+use atomic_refcell::AtomicRefCell;
+use core::any::{self, TypeId};
+use hashbrown::hash_map::Entry;
+
+/// Storage for components that are not required to be `Send` or `Sync`.
+///
+/// The storage must only be owned by types that are neither `Send` nor `Sync`,
+/// so the components never leave the thread that created them.
+#[derive(Default, Debug)]
+pub(crate) struct LocalComponentStorage {
+    metadata: FxHashMap<TypeId, usize>,
+    components: Vec<AtomicRefCell<ComponentSparseSet>>,
+}
+
+impl LocalComponentStorage {
+    pub fn register<T>(&mut self, allocator: SharedAllocator) -> bool
+    where
+        T: 'static,
+    {
+        let Entry::Vacant(entry) = self.metadata.entry(TypeId::of::<T>()) else {
+            return false;
+        };
+
+        entry.insert(self.components.len());
+
+        self.components
+            .push(AtomicRefCell::new(ComponentSparseSet::new_local::<T>(
+                allocator,
+            )));
+
+        true
+    }
+
+    #[must_use]
+    pub fn is_registered<T>(&self) -> bool
+    where
+        T: 'static,
+    {
+        self.metadata.contains_key(&TypeId::of::<T>())
+    }
+
+    pub fn insert<T>(&mut self, entity: Entity, component: T) -> Option<T>
+    where
+        T: 'static,
+    {
+        let sparse_set = self.get_mut::<T>();
+        unsafe { sparse_set.insert(entity, component) }
+    }
+
+    pub fn remove<T>(&mut self, entity: Entity) -> Option<T>
+    where
+        T: 'static,
+    {
+        let sparse_set = self.get_mut::<T>();
+        unsafe { sparse_set.remove(entity) }
+    }
+
+    pub fn strip(&mut self, entity: Entity) {
+        for sparse_set in &mut self.components {
+            sparse_set.get_mut().delete_dyn(entity);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        for sparse_set in &mut self.components {
+            sparse_set.get_mut().clear();
+        }
+    }
+
+    #[must_use]
+    pub fn borrow<T>(&self) -> View<'_, T>
+    where
+        T: 'static,
+    {
+        View::new(self.get::<T>().borrow())
+    }
+
+    #[must_use]
+    pub fn borrow_mut<T>(&self) -> ViewMut<'_, T>
+    where
+        T: 'static,
+    {
+        ViewMut::new(self.get::<T>().borrow_mut())
+    }
+
+    #[must_use]
+    fn get<T>(&self) -> &AtomicRefCell<ComponentSparseSet>
+    where
+        T: 'static,
+    {
+        let Some(&storage_index) = self.metadata.get(&TypeId::of::<T>()) else {
+            panic_missing_local_comp::<T>();
+        };
+
+        unsafe { self.components.get_unchecked(storage_index) }
+    }
+
+    #[must_use]
+    fn get_mut<T>(&mut self) -> &mut ComponentSparseSet
+    where
+        T: 'static,
+    {
+        let Some(&storage_index) = self.metadata.get(&TypeId::of::<T>()) else {
+            panic_missing_local_comp::<T>();
+        };
+
+        unsafe { self.components.get_unchecked_mut(storage_index).get_mut() }
+    }
+}
+
+#[cold]
+#[inline(never)]
+fn panic_missing_local_comp<T>() -> ! {
+    panic!(
+        "Local component '{}' was not registered",
+        any::type_name::<T>(),
+    );
+}
@@ -4,23 +4,35 @@ mod component_data;
 mod component_set;
 mod component_sparse_set;
 mod component_storage;
+mod entity_components;
 mod group;
+mod group_event;
 mod group_info;
 mod group_layout;
 mod group_mask;
+mod group_sort;
+mod group_summary;
+mod local_storage;
+mod non_owning_group;
 mod storage_mask;
 mod view;
 
 pub use self::component_data::*;
 pub use self::component_set::*;
+pub use self::entity_components::*;
+pub use self::group_event::*;
 pub use self::group_info::*;
 pub use self::group_layout::*;
+pub use self::group_sort::*;
+pub use self::group_summary::*;
 pub use self::view::*;
 
 pub(crate) use self::component_sparse_set::*;
 pub(crate) use self::component_storage::*;
 pub(crate) use self::group::*;
 pub(crate) use self::group_mask::*;
+pub(crate) use self::local_storage::*;
+pub(crate) use self::non_owning_group::*;
 pub(crate) use self::storage_mask::*;
 
 /// Marker trait for components that can be added to entities.
@@ -0,0 +1,101 @@
+use crate::component::{ComponentData, ComponentSparseSet, GroupMask};
+use crate::entity::{Entity, EntitySparseSet};
+use alloc::vec::Vec;
+use atomic_refcell::AtomicRefCell;
+
+/// Group that tracks the entities which have all of its components, without
+/// reordering the component storages.
+#[derive(Debug)]
+pub(crate) struct NonOwningGroup {
+    pub components: Vec<ComponentData>,
+    pub storages: Vec<usize>,
+    pub entities: EntitySparseSet,
+}
+
+/// Non-owning groups that can drive the iteration of a query, found once when
+/// the query is borrowed.
+#[derive(Clone, Copy, Default, Debug)]
+pub(crate) struct NonOwningMatch {
+    /// The groups whose component types are all required by the query.
+    pub subset_mask: GroupMask,
+    /// The group whose component types are exactly the ones required by the
+    /// query, if any.
+    pub exact: Option<usize>,
+}
+
+impl NonOwningGroup {
+    /// Rebuilds the entity list from the smallest storage of the group.
+    pub unsafe fn rebuild(&mut self, components: &[AtomicRefCell<ComponentSparseSet>]) {
+        self.entities.clear();
+
+        let Some(smallest) = self
+            .storages
+            .iter()
+            .map(|&storage| &*components.get_unchecked(storage).as_ptr())
+            .min_by_key(|sparse_set| sparse_set.len())
+        else {
+            return;
+        };
+
+        for &entity in smallest.entities() {
+            if self.contains_all(components, entity) {
+                self.entities.insert(entity);
+            }
+        }
+    }
+
+    #[must_use]
+    unsafe fn contains_all(
+        &self,
+        components: &[AtomicRefCell<ComponentSparseSet>],
+        entity: Entity,
+    ) -> bool {
+        self.storages
+            .iter()
+            .all(|&storage| (*components.get_unchecked(storage).as_ptr()).contains(entity))
+    }
+}
+
+/// Adds `entity` to the non-owning groups in `group_mask` whose components it
+/// now has.
+pub(crate) unsafe fn join_non_owning(
+    components: &[AtomicRefCell<ComponentSparseSet>],
+    groups: &mut [NonOwningGroup],
+    group_mask: GroupMask,
+    entity: Entity,
+) {
+    for group_index in group_mask.iter_bit_indexes() {
+        let group = groups.get_unchecked_mut(group_index as usize);
+
+        if !group.entities.contains(entity) && group.contains_all(components, entity) {
+            group.entities.insert(entity);
+        }
+    }
+}
+
+/// Removes `entity` from the non-owning groups in `group_mask`.
+pub(crate) unsafe fn leave_non_owning(
+    groups: &mut [NonOwningGroup],
+    group_mask: GroupMask,
+    entity: Entity,
+) {
+    for group_index in group_mask.iter_bit_indexes() {
+        groups
+            .get_unchecked_mut(group_index as usize)
+            .entities
+            .remove(entity);
+    }
+}
+
+/// Rebuilds the entity lists of the non-owning groups in `group_mask`.
+pub(crate) unsafe fn rebuild_non_owning(
+    components: &[AtomicRefCell<ComponentSparseSet>],
+    groups: &mut [NonOwningGroup],
+    group_mask: GroupMask,
+) {
+    for group_index in group_mask.iter_bit_indexes() {
+        groups
+            .get_unchecked_mut(group_index as usize)
+            .rebuild(components);
+    }
+}
@@ -1,8 +1,14 @@
 use crate::component::MAX_GROUP_ARITY;
-use core::num::NonZeroU16;
+use core::num::NonZero;
+
+#[cfg(not(feature = "wide-groups"))]
+type StorageMaskBits = u16;
+
+#[cfg(feature = "wide-groups")]
+type StorageMaskBits = u32;
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
-pub(crate) struct StorageMask(u16);
+pub(crate) struct StorageMask(StorageMaskBits);
 
 impl StorageMask {
     pub const EMPTY: Self = Self(0);
@@ -10,11 +16,15 @@ impl StorageMask {
     #[inline]
     #[must_use]
     pub fn from_to(from: usize, to: usize) -> Self {
-        assert!(from < MAX_GROUP_ARITY);
-        assert!(to < MAX_GROUP_ARITY);
         assert!(from <= to);
+        assert!(to <= MAX_GROUP_ARITY);
+
+        let ones = match to - from {
+            0 => 0,
+            count => StorageMaskBits::MAX >> (StorageMaskBits::BITS as usize - count),
+        };
 
-        Self(((1 << (to - from)) - 1) << from)
+        Self(ones << from)
     }
 }
 
@@ -26,14 +36,14 @@ impl From<NonZeroStorageMask> for StorageMask {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
-pub(crate) struct NonZeroStorageMask(NonZeroU16);
+pub(crate) struct NonZeroStorageMask(NonZero<StorageMaskBits>);
 
 impl NonZeroStorageMask {
     #[inline]
     #[must_use]
     pub fn single(index: usize) -> Self {
         assert!(index < MAX_GROUP_ARITY);
-        Self(NonZeroU16::new(1 << index).unwrap())
+        Self(NonZero::new(1 << index).unwrap())
     }
 }
 
@@ -57,7 +67,7 @@ macro_rules! impl_common {
 
         impl ::core::fmt::Debug for $Ty {
             fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
-                ::core::write!(f, "{:0>16b}", self.0)
+                ::core::write!(f, "{:0>1$b}", self.0, StorageMaskBits::BITS as usize)
             }
         }
     };
@@ -1,4 +1,4 @@
-use crate::component::{Component, ComponentSparseSet};
+use crate::component::ComponentSparseSet;
 use crate::entity::{Entity, SparseVec};
 use atomic_refcell::{AtomicRef, AtomicRefMut};
 use core::fmt;
@@ -33,7 +33,7 @@ pub struct ViewMut<'a, T> {
 
 impl<'a, T> ViewMut<'a, T>
 where
-    T: Component,
+    T: 'static,
 {
     #[inline]
     #[must_use]
@@ -60,7 +60,7 @@ where
 
 impl<T> IndexMut<Entity> for ViewMut<'_, T>
 where
-    T: Component,
+    T: 'static,
 {
     fn index_mut(&mut self, entity: Entity) -> &mut Self::Output {
         self.get_mut(entity).unwrap()
@@ -71,7 +71,7 @@ macro_rules! impl_view_common {
     ($View:ident) => {
         impl<'a, T> $View<'a, T>
         where
-            T: Component,
+            T: 'static,
         {
             /// Returns a reference to the component mapped to `entity` if it exists.
             #[must_use]
@@ -122,7 +122,7 @@ macro_rules! impl_view_common {
 
         impl<T> Index<Entity> for $View<'_, T>
         where
-            T: Component,
+            T: 'static,
         {
             type Output = T;
 
@@ -133,7 +133,7 @@ macro_rules! impl_view_common {
 
         impl<T> fmt::Debug for $View<'_, T>
         where
-            T: Component + fmt::Debug,
+            T: fmt::Debug + 'static,
         {
             fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
                 let entries = self.entities().iter().zip(self.as_slice());
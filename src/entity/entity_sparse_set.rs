@@ -1,14 +1,22 @@
+use crate::allocator::{AllocVec, SharedAllocator};
 use crate::entity::{Entity, SparseVec, SparseVecSlot};
-use alloc::vec::Vec;
 use core::fmt;
 
 #[derive(Clone, Default)]
 pub(crate) struct EntitySparseSet {
     sparse: SparseVec,
-    entities: Vec<Entity>,
+    entities: AllocVec<Entity>,
 }
 
 impl EntitySparseSet {
+    #[must_use]
+    pub fn new_in(allocator: SharedAllocator) -> Self {
+        Self {
+            sparse: SparseVec::new_in(allocator.clone()),
+            entities: AllocVec::new_in(allocator),
+        }
+    }
+
     pub fn insert(&mut self, entity: Entity) {
         let slot = self.sparse.get_mut_or_allocate_at(entity.sparse());
 
@@ -75,6 +83,6 @@ impl EntitySparseSet {
 
 impl fmt::Debug for EntitySparseSet {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_set().entries(&self.entities).finish()
+        f.debug_set().entries(self.entities.iter()).finish()
     }
 }
@@ -1,3 +1,4 @@
+use crate::allocator::SharedAllocator;
 use crate::entity::{Entity, EntityAllocator, EntitySparseSet};
 
 #[derive(Default, Debug)]
@@ -7,6 +8,14 @@ pub(crate) struct EntityStorage {
 }
 
 impl EntityStorage {
+    #[must_use]
+    pub fn new_in(allocator: SharedAllocator) -> Self {
+        Self {
+            allocator: EntityAllocator::default(),
+            entities: EntitySparseSet::new_in(allocator),
+        }
+    }
+
     #[must_use]
     pub fn create(&mut self) -> Entity {
         if self.allocator.should_maintain_recyled() {
@@ -1,11 +1,11 @@
+use crate::allocator::{AllocVec, SharedAllocator};
 use crate::entity::{Entity, Version};
-use alloc::vec::Vec;
-use core::{fmt, iter, mem};
+use core::{fmt, mem};
 
 /// Maps entities to dense indexes.
 #[derive(Clone, Default)]
 pub struct SparseVec {
-    slots: Vec<Option<SparseVecSlot>>,
+    slots: AllocVec<Option<SparseVecSlot>>,
 }
 
 impl SparseVec {
@@ -13,7 +13,18 @@ impl SparseVec {
     #[inline]
     #[must_use]
     pub const fn new() -> Self {
-        Self { slots: Vec::new() }
+        Self {
+            slots: AllocVec::new(),
+        }
+    }
+
+    /// Creates a new sparse vec that allocates memory from `allocator`.
+    #[inline]
+    #[must_use]
+    pub(crate) const fn new_in(allocator: SharedAllocator) -> Self {
+        Self {
+            slots: AllocVec::new_in(allocator),
+        }
     }
 
     /// Returns the dense index mapped to `entity`, if any.
@@ -123,8 +134,8 @@ impl SparseVec {
 
     #[cold]
     fn extend_to_index(&mut self, index: usize) {
-        let extra_len = index.checked_next_power_of_two().unwrap_or(index) - self.slots.len() + 1;
-        self.slots.extend(iter::repeat_n(None, extra_len));
+        let new_len = index.checked_next_power_of_two().unwrap_or(index) + 1;
+        self.slots.resize(new_len, None);
     }
 }
 
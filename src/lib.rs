@@ -27,8 +27,11 @@
 //!
 //! # Features
 //!
-//! - `std` (on by default): link to the `std` crate.
+//! - `std` (on by default): link to the `std` crate and enable query
+//!   profiling.
 //! - `parallel`: enable parallel iterators.
+//! - `wide-groups`: raise the maximum number of groups from 64 to 128 and the
+//!   maximum group arity from 16 to 32.
 //!
 //! # Usage
 //!
@@ -43,6 +46,7 @@
 
 extern crate alloc;
 
+pub mod allocator;
 pub mod component;
 pub mod entity;
 pub mod query;
@@ -0,0 +1,708 @@
+use crate::component::{Component, ComponentData, QueryGroupInfo, View};
+use crate::entity::{Entity, SparseVec};
+use crate::query::{Query, QueryPart, ReadOnlyQueryPart};
+use crate::World;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use core::ops::Range;
+
+/// Filter that matches entities matched by any part of the query `Q`.
+///
+/// Filters yield `()`. They can be nested in each other and used anywhere in a
+/// query, most commonly in [`include`](crate::query::QueryAll::include) and
+/// [`exclude`](crate::query::QueryAll::exclude) filters. This one is
+/// evaluated per entity, so queries that contain it are always iterated
+/// sparsely.
+pub struct Or<Q>(PhantomData<Q>);
+
+/// Filter that matches entities matched by all parts of the query `Q`.
+///
+/// Useful for nesting inside [`Or`] and [`Not`]. It keeps the grouping
+/// information of `Q`, so it can be iterated densely like the parts of `Q`
+/// would be, and the shortest storage of `Q` drives sparse iteration.
+pub struct And<Q>(PhantomData<Q>);
+
+/// Filter that matches entities not matched by all parts of the query `Q`.
+///
+/// Outside of [`Or`] filters, it is planned like an
+/// [`exclude`](crate::query::QueryAll::exclude) filter, so the query can be
+/// iterated densely if `Q` holds exactly the components a group adds over its
+/// parent. Queries can only be bounded by one group, so a query with more than
+/// one `Not` filter or with both a `Not` and an "exclude" filter is iterated
+/// sparsely.
+pub struct Not<Q>(PhantomData<Q>);
+
+/// Query part that matches entities with at least one of the parts of the
+/// tuple `Q` and yields an [`Option`] for each of them.
+///
+/// Like [`Or`], queries that contain it are always iterated sparsely.
+pub struct AnyOf<Q>(PhantomData<Q>);
+
+/// Query part that matches entities with a component of type `T` without
+/// borrowing it mutably or yielding it.
+///
+/// Unlike other filters, it keeps grouping information, so it can be used in
+/// dense queries like `&T`.
+pub struct With<T>(PhantomData<T>);
+
+/// Filter that matches entities without a component of type `T`.
+///
+/// Unlike the [`exclude`](crate::query::QueryAll::exclude) filter, which lets
+/// grouped queries be iterated densely when `T` is the component a group adds
+/// over its parent, queries that contain it are always iterated sparsely.
+pub struct Without<T>(PhantomData<T>);
+
+/// Query part that yields whether entities have a component of type `T`. It
+/// doesn't restrict the matched entities.
+pub struct Has<T>(PhantomData<T>);
+
+unsafe impl<T> QueryPart for With<T>
+where
+    T: Component,
+{
+    type View<'a> = View<'a, T>;
+    type Item<'a> = ();
+    type Slice<'a> = ();
+    type Sparse<'a> = &'a SparseVec;
+    type SparseKey = ();
+    type Data<'a> = ();
+    type State = usize;
+
+    fn borrow(world: &World) -> Self::View<'_> {
+        world.borrow::<T>()
+    }
+
+    fn prepare(world: &World) -> Self::State {
+        world.components.storage_index::<T>()
+    }
+
+    unsafe fn borrow_prepared(world: &World, state: Self::State) -> Self::View<'_> {
+        world.components.borrow_at::<T>(state)
+    }
+
+    fn borrow_with_group_info(world: &World) -> (Self::View<'_>, Option<QueryGroupInfo>) {
+        <&T as QueryPart>::borrow_with_group_info(world)
+    }
+
+    fn add_required_components(components: &mut Vec<ComponentData>) {
+        components.push(ComponentData::new::<T>());
+    }
+
+    fn driver_component(_view: &Self::View<'_>) -> Option<ComponentData> {
+        Some(ComponentData::new::<T>())
+    }
+
+    fn contains(view: &Self::View<'_>, entity: Entity) -> bool {
+        view.contains(entity)
+    }
+
+    fn get_sparse_key(view: &Self::View<'_>, entity: Entity) -> Option<Self::SparseKey> {
+        view.contains(entity).then_some(())
+    }
+
+    unsafe fn get_sparse<'a>(
+        _view: &'a mut Self::View<'_>,
+        _key: Self::SparseKey,
+    ) -> Self::Item<'a> {
+        // Empty
+    }
+
+    fn split_filter_parts<'a>(
+        view: &'a Self::View<'_>,
+    ) -> (Option<&'a [Entity]>, Self::Sparse<'a>) {
+        (Some(view.entities()), view.sparse())
+    }
+
+    fn split_sparse_parts<'a>(
+        view: &'a Self::View<'_>,
+    ) -> (Option<&'a [Entity]>, Self::Sparse<'a>, Self::Data<'a>) {
+        (Some(view.entities()), view.sparse(), ())
+    }
+
+    fn split_dense_parts<'a>(view: &'a Self::View<'_>) -> (Option<&'a [Entity]>, Self::Data<'a>) {
+        (Some(view.entities()), ())
+    }
+
+    fn contains_raw(sparse: Self::Sparse<'_>, sparse_index: usize) -> bool {
+        sparse.contains_sparse(sparse_index)
+    }
+
+    fn get_dense_index_raw(_sparse: Self::Sparse<'_>, _entity: Entity) -> Option<usize> {
+        None
+    }
+
+    fn get_sparse_key_raw(sparse: Self::Sparse<'_>, entity: Entity) -> Option<Self::SparseKey> {
+        sparse.contains_sparse(entity.sparse()).then_some(())
+    }
+
+    unsafe fn get_sparse_raw(_data: Self::Data<'_>, _key: Self::SparseKey) -> Self::Item<'_> {
+        // Empty
+    }
+
+    unsafe fn get_dense_raw(
+        _data: Self::Data<'_>,
+        _index: usize,
+        _entity: Entity,
+    ) -> Self::Item<'_> {
+        // Empty
+    }
+
+    unsafe fn slice_raw<'a>(
+        _data: Self::Data<'_>,
+        _entities: &'a [Entity],
+        _range: Range<usize>,
+    ) -> Self::Slice<'a> {
+        // Empty
+    }
+}
+
+unsafe impl<T> QueryPart for Without<T>
+where
+    T: Component,
+{
+    type View<'a> = View<'a, T>;
+    type Item<'a> = ();
+    type Slice<'a> = ();
+    type Sparse<'a> = &'a SparseVec;
+    type SparseKey = ();
+    type Data<'a> = ();
+    type State = usize;
+
+    const SPARSE_ONLY: bool = true;
+
+    fn borrow(world: &World) -> Self::View<'_> {
+        world.borrow::<T>()
+    }
+
+    fn prepare(world: &World) -> Self::State {
+        world.components.storage_index::<T>()
+    }
+
+    unsafe fn borrow_prepared(world: &World, state: Self::State) -> Self::View<'_> {
+        world.components.borrow_at::<T>(state)
+    }
+
+    fn borrow_with_group_info(world: &World) -> (Self::View<'_>, Option<QueryGroupInfo>) {
+        (world.borrow::<T>(), None)
+    }
+
+    fn add_required_components(_components: &mut Vec<ComponentData>) {
+        // Empty
+    }
+
+    fn contains(view: &Self::View<'_>, entity: Entity) -> bool {
+        !view.contains(entity)
+    }
+
+    fn get_sparse_key(view: &Self::View<'_>, entity: Entity) -> Option<Self::SparseKey> {
+        (!view.contains(entity)).then_some(())
+    }
+
+    unsafe fn get_sparse<'a>(
+        _view: &'a mut Self::View<'_>,
+        _key: Self::SparseKey,
+    ) -> Self::Item<'a> {
+        // Empty
+    }
+
+    fn split_filter_parts<'a>(
+        view: &'a Self::View<'_>,
+    ) -> (Option<&'a [Entity]>, Self::Sparse<'a>) {
+        (None, view.sparse())
+    }
+
+    fn split_sparse_parts<'a>(
+        view: &'a Self::View<'_>,
+    ) -> (Option<&'a [Entity]>, Self::Sparse<'a>, Self::Data<'a>) {
+        (None, view.sparse(), ())
+    }
+
+    fn split_dense_parts<'a>(_view: &'a Self::View<'_>) -> (Option<&'a [Entity]>, Self::Data<'a>) {
+        (None, ())
+    }
+
+    fn contains_raw(sparse: Self::Sparse<'_>, sparse_index: usize) -> bool {
+        !sparse.contains_sparse(sparse_index)
+    }
+
+    fn get_dense_index_raw(_sparse: Self::Sparse<'_>, _entity: Entity) -> Option<usize> {
+        None
+    }
+
+    fn get_sparse_key_raw(sparse: Self::Sparse<'_>, entity: Entity) -> Option<Self::SparseKey> {
+        (!sparse.contains_sparse(entity.sparse())).then_some(())
+    }
+
+    unsafe fn get_sparse_raw(_data: Self::Data<'_>, _key: Self::SparseKey) -> Self::Item<'_> {
+        // Empty
+    }
+
+    unsafe fn get_dense_raw(
+        _data: Self::Data<'_>,
+        _index: usize,
+        _entity: Entity,
+    ) -> Self::Item<'_> {
+        // Empty
+    }
+
+    unsafe fn slice_raw<'a>(
+        _data: Self::Data<'_>,
+        _entities: &'a [Entity],
+        _range: Range<usize>,
+    ) -> Self::Slice<'a> {
+        // Empty
+    }
+}
+
+unsafe impl<T> QueryPart for Has<T>
+where
+    T: Component,
+{
+    type View<'a> = View<'a, T>;
+    type Item<'a> = bool;
+    type Slice<'a> = ();
+    type Sparse<'a> = &'a SparseVec;
+    type SparseKey = bool;
+    type Data<'a> = &'a SparseVec;
+    type State = usize;
+
+    fn borrow(world: &World) -> Self::View<'_> {
+        world.borrow::<T>()
+    }
+
+    fn prepare(world: &World) -> Self::State {
+        world.components.storage_index::<T>()
+    }
+
+    unsafe fn borrow_prepared(world: &World, state: Self::State) -> Self::View<'_> {
+        world.components.borrow_at::<T>(state)
+    }
+
+    fn borrow_with_group_info(world: &World) -> (Self::View<'_>, Option<QueryGroupInfo>) {
+        (world.borrow::<T>(), Some(QueryGroupInfo::Empty))
+    }
+
+    fn add_required_components(_components: &mut Vec<ComponentData>) {
+        // Empty
+    }
+
+    fn contains(_view: &Self::View<'_>, _entity: Entity) -> bool {
+        true
+    }
+
+    fn get_sparse_key(view: &Self::View<'_>, entity: Entity) -> Option<Self::SparseKey> {
+        Some(view.contains(entity))
+    }
+
+    unsafe fn get_sparse<'a>(
+        _view: &'a mut Self::View<'_>,
+        key: Self::SparseKey,
+    ) -> Self::Item<'a> {
+        key
+    }
+
+    fn split_filter_parts<'a>(
+        view: &'a Self::View<'_>,
+    ) -> (Option<&'a [Entity]>, Self::Sparse<'a>) {
+        (None, view.sparse())
+    }
+
+    fn split_sparse_parts<'a>(
+        view: &'a Self::View<'_>,
+    ) -> (Option<&'a [Entity]>, Self::Sparse<'a>, Self::Data<'a>) {
+        (None, view.sparse(), view.sparse())
+    }
+
+    fn split_dense_parts<'a>(view: &'a Self::View<'_>) -> (Option<&'a [Entity]>, Self::Data<'a>) {
+        (None, view.sparse())
+    }
+
+    fn contains_raw(_sparse: Self::Sparse<'_>, _sparse_index: usize) -> bool {
+        true
+    }
+
+    fn get_dense_index_raw(_sparse: Self::Sparse<'_>, _entity: Entity) -> Option<usize> {
+        None
+    }
+
+    fn get_sparse_key_raw(sparse: Self::Sparse<'_>, entity: Entity) -> Option<Self::SparseKey> {
+        Some(sparse.contains(entity))
+    }
+
+    unsafe fn get_sparse_raw(_data: Self::Data<'_>, key: Self::SparseKey) -> Self::Item<'_> {
+        key
+    }
+
+    unsafe fn get_dense_raw(
+        sparse: Self::Data<'_>,
+        _index: usize,
+        entity: Entity,
+    ) -> Self::Item<'_> {
+        sparse.contains(entity)
+    }
+
+    unsafe fn slice_raw<'a>(
+        _data: Self::Data<'_>,
+        _entities: &'a [Entity],
+        _range: Range<usize>,
+    ) -> Self::Slice<'a> {
+        // Empty
+    }
+}
+
+unsafe impl<T> ReadOnlyQueryPart for With<T>
+where
+    T: Component,
+{
+    // Empty
+}
+
+unsafe impl<T> ReadOnlyQueryPart for Without<T>
+where
+    T: Component,
+{
+    // Empty
+}
+
+unsafe impl<T> ReadOnlyQueryPart for Has<T>
+where
+    T: Component,
+{
+    // Empty
+}
+
+macro_rules! impl_filter {
+    (
+        $Filter:ident,
+        requires_all: $requires_all:literal,
+        sparse_only: $sparse_only:expr,
+        excludes: $excludes:expr,
+        group_info: |$world:ident| $group_info:expr,
+        exclude_group_info: |$exclude_world:ident| $exclude_group_info:expr,
+        contains: |$view:ident, $entity:ident| $contains:expr,
+        contains_raw: |$sparse:ident, $sparse_index:ident| $contains_raw:expr $(,)?
+    ) => {
+        unsafe impl<Q> QueryPart for $Filter<Q>
+        where
+            Q: Query,
+        {
+            type View<'a> = Q::View<'a>;
+            type Item<'a> = ();
+            type Slice<'a> = ();
+            type Sparse<'a> = Q::Sparse<'a>;
+            type SparseKey = ();
+            type Data<'a> = ();
+            type State = Q::State;
+
+            const SPARSE_ONLY: bool = $sparse_only;
+
+            const EXCLUDES: bool = $excludes;
+
+            fn borrow(world: &World) -> Self::View<'_> {
+                Q::borrow(world)
+            }
+
+            fn prepare(world: &World) -> Self::State {
+                Q::prepare(world)
+            }
+
+            unsafe fn borrow_prepared(world: &World, state: Self::State) -> Self::View<'_> {
+                Q::borrow_prepared(world, state)
+            }
+
+            fn borrow_with_group_info($world: &World) -> (Self::View<'_>, Option<QueryGroupInfo>) {
+                $group_info
+            }
+
+            fn exclude_group_info($exclude_world: &World) -> Option<QueryGroupInfo> {
+                $exclude_group_info
+            }
+
+            fn add_required_components(components: &mut Vec<ComponentData>) {
+                if $requires_all {
+                    Q::add_required_components(components);
+                }
+            }
+
+            fn driver_component(view: &Self::View<'_>) -> Option<ComponentData> {
+                if $requires_all {
+                    Q::driver_component(view)
+                } else {
+                    None
+                }
+            }
+
+            fn contains($view: &Self::View<'_>, $entity: Entity) -> bool {
+                $contains
+            }
+
+            fn get_sparse_key(view: &Self::View<'_>, entity: Entity) -> Option<Self::SparseKey> {
+                <Self as QueryPart>::contains(view, entity).then_some(())
+            }
+
+            unsafe fn get_sparse<'a>(
+                _view: &'a mut Self::View<'_>,
+                _key: Self::SparseKey,
+            ) -> Self::Item<'a> {
+                // Empty
+            }
+
+            fn split_filter_parts<'a>(
+                view: &'a Self::View<'_>,
+            ) -> (Option<&'a [Entity]>, Self::Sparse<'a>) {
+                let (entities, sparse) = Q::split_filter_parts(view);
+                (entities.filter(|_| $requires_all), sparse)
+            }
+
+            fn split_sparse_parts<'a>(
+                view: &'a Self::View<'_>,
+            ) -> (Option<&'a [Entity]>, Self::Sparse<'a>, Self::Data<'a>) {
+                let (entities, sparse) = <Self as QueryPart>::split_filter_parts(view);
+                (entities, sparse, ())
+            }
+
+            fn split_dense_parts<'a>(
+                view: &'a Self::View<'_>,
+            ) -> (Option<&'a [Entity]>, Self::Data<'a>) {
+                let (entities, _) = Q::split_dense_parts(view);
+                (entities.filter(|_| $requires_all), ())
+            }
+
+            fn contains_raw($sparse: Self::Sparse<'_>, $sparse_index: usize) -> bool {
+                $contains_raw
+            }
+
+            fn get_dense_index_raw(_sparse: Self::Sparse<'_>, _entity: Entity) -> Option<usize> {
+                None
+            }
+
+            fn get_sparse_key_raw(
+                sparse: Self::Sparse<'_>,
+                entity: Entity,
+            ) -> Option<Self::SparseKey> {
+                <Self as QueryPart>::contains_raw(sparse, entity.sparse()).then_some(())
+            }
+
+            unsafe fn get_sparse_raw(
+                _data: Self::Data<'_>,
+                _key: Self::SparseKey,
+            ) -> Self::Item<'_> {
+                // Empty
+            }
+
+            unsafe fn get_dense_raw(
+                _data: Self::Data<'_>,
+                _index: usize,
+                _entity: Entity,
+            ) -> Self::Item<'_> {
+                // Empty
+            }
+
+            unsafe fn slice_raw<'a>(
+                _data: Self::Data<'_>,
+                _entities: &'a [Entity],
+                _range: Range<usize>,
+            ) -> Self::Slice<'a> {
+                // Empty
+            }
+        }
+
+        unsafe impl<Q> ReadOnlyQueryPart for $Filter<Q>
+        where
+            Q: Query,
+        {
+            // Empty
+        }
+    };
+}
+
+impl_filter!(
+    Or,
+    requires_all: false,
+    sparse_only: true,
+    excludes: false,
+    group_info: |world| (Q::borrow(world), None),
+    exclude_group_info: |_world| Some(QueryGroupInfo::Empty),
+    contains: |view, entity| !Q::contains_none(view, entity),
+    contains_raw: |sparse, sparse_index| !Q::contains_none_raw(sparse, sparse_index),
+);
+
+impl_filter!(
+    And,
+    requires_all: true,
+    sparse_only: Q::SPARSE_ONLY,
+    excludes: Q::EXCLUDES,
+    group_info: |world| Q::borrow_with_group_info(world),
+    exclude_group_info: |world| Q::exclude_group_info(world),
+    contains: |view, entity| Q::contains_all(view, entity),
+    contains_raw: |sparse, sparse_index| Q::contains_all_raw(sparse, sparse_index),
+);
+
+impl_filter!(
+    Not,
+    requires_all: false,
+    sparse_only: Q::SPARSE_ONLY,
+    excludes: true,
+    group_info: |world| (Q::borrow(world), Some(QueryGroupInfo::Empty)),
+    exclude_group_info: |world| not_exclude_group_info::<Q>(world),
+    contains: |view, entity| !Q::contains_all(view, entity),
+    contains_raw: |sparse, sparse_index| !Q::contains_all_raw(sparse, sparse_index),
+);
+
+/// Returns the grouping information of the components that entities matched
+/// by [`Not<Q>`] lack at least one of.
+fn not_exclude_group_info<Q>(world: &World) -> Option<QueryGroupInfo>
+where
+    Q: Query,
+{
+    if Q::EXCLUDES {
+        return None;
+    }
+
+    let mut components = Vec::new();
+    Q::add_required_components(&mut components);
+
+    world
+        .components
+        .components_group_info(&components)
+        .map(QueryGroupInfo::Many)
+}
+
+macro_rules! impl_any_of {
+    ($(($Ty:ident, $idx:tt)),+) => {
+        unsafe impl<$($Ty,)+> QueryPart for AnyOf<($($Ty,)+)>
+        where
+            $($Ty: QueryPart,)+
+        {
+            type View<'a> = ($($Ty::View<'a>,)+);
+            type Item<'a> = ($(Option<$Ty::Item<'a>>,)+);
+            type Slice<'a> = ();
+            type Sparse<'a> = ($($Ty::Sparse<'a>,)+);
+            type SparseKey = Entity;
+            type Data<'a> = ($(($Ty::Sparse<'a>, $Ty::Data<'a>),)+);
+            type State = ($($Ty::State,)+);
+
+            const SPARSE_ONLY: bool = true;
+
+            fn borrow(world: &World) -> Self::View<'_> {
+                ($($Ty::borrow(world),)+)
+            }
+
+            fn prepare(world: &World) -> Self::State {
+                ($($Ty::prepare(world),)+)
+            }
+
+            unsafe fn borrow_prepared(world: &World, state: Self::State) -> Self::View<'_> {
+                ($($Ty::borrow_prepared(world, state.$idx),)+)
+            }
+
+            fn borrow_with_group_info(world: &World) -> (Self::View<'_>, Option<QueryGroupInfo>) {
+                (<Self as QueryPart>::borrow(world), None)
+            }
+
+            fn add_required_components(_components: &mut Vec<ComponentData>) {
+                // Empty
+            }
+
+            fn contains(view: &Self::View<'_>, entity: Entity) -> bool {
+                $($Ty::contains(&view.$idx, entity))||+
+            }
+
+            fn get_sparse_key(view: &Self::View<'_>, entity: Entity) -> Option<Self::SparseKey> {
+                <Self as QueryPart>::contains(view, entity).then_some(entity)
+            }
+
+            unsafe fn get_sparse<'a>(
+                view: &'a mut Self::View<'_>,
+                entity: Self::SparseKey,
+            ) -> Self::Item<'a> {
+                ($(
+                    $Ty::get_sparse_key(&view.$idx, entity)
+                        .map(|key| $Ty::get_sparse(&mut view.$idx, key)),
+                )+)
+            }
+
+            fn split_filter_parts<'a>(
+                view: &'a Self::View<'_>,
+            ) -> (Option<&'a [Entity]>, Self::Sparse<'a>) {
+                (None, ($($Ty::split_filter_parts(&view.$idx).1,)+))
+            }
+
+            fn split_sparse_parts<'a>(
+                view: &'a Self::View<'_>,
+            ) -> (Option<&'a [Entity]>, Self::Sparse<'a>, Self::Data<'a>) {
+                let parts = ($($Ty::split_sparse_parts(&view.$idx),)+);
+                (None, ($(parts.$idx.1,)+), ($((parts.$idx.1, parts.$idx.2),)+))
+            }
+
+            fn split_dense_parts<'a>(
+                view: &'a Self::View<'_>,
+            ) -> (Option<&'a [Entity]>, Self::Data<'a>) {
+                let (_, _, data) = <Self as QueryPart>::split_sparse_parts(view);
+                (None, data)
+            }
+
+            fn contains_raw(sparse: Self::Sparse<'_>, sparse_index: usize) -> bool {
+                $($Ty::contains_raw(sparse.$idx, sparse_index))||+
+            }
+
+            fn get_dense_index_raw(_sparse: Self::Sparse<'_>, _entity: Entity) -> Option<usize> {
+                None
+            }
+
+            fn get_sparse_key_raw(
+                sparse: Self::Sparse<'_>,
+                entity: Entity,
+            ) -> Option<Self::SparseKey> {
+                <Self as QueryPart>::contains_raw(sparse, entity.sparse()).then_some(entity)
+            }
+
+            unsafe fn get_sparse_raw(
+                data: Self::Data<'_>,
+                entity: Self::SparseKey,
+            ) -> Self::Item<'_> {
+                ($(
+                    $Ty::get_sparse_key_raw(data.$idx.0, entity)
+                        .map(|key| $Ty::get_sparse_raw(data.$idx.1, key)),
+                )+)
+            }
+
+            unsafe fn get_dense_raw(
+                data: Self::Data<'_>,
+                _index: usize,
+                entity: Entity,
+            ) -> Self::Item<'_> {
+                <Self as QueryPart>::get_sparse_raw(data, entity)
+            }
+
+            unsafe fn slice_raw<'a>(
+                _data: Self::Data<'_>,
+                _entities: &'a [Entity],
+                _range: Range<usize>,
+            ) -> Self::Slice<'a> {
+                // Empty
+            }
+        }
+
+        unsafe impl<$($Ty,)+> ReadOnlyQueryPart for AnyOf<($($Ty,)+)>
+        where
+            $($Ty: ReadOnlyQueryPart,)+
+        {
+            // Empty
+        }
+    };
+}
+
+#[rustfmt::skip]
+mod impls {
+    use super::*;
+
+    impl_any_of!((A, 0));
+    impl_any_of!((A, 0), (B, 1));
+    impl_any_of!((A, 0), (B, 1), (C, 2));
+    impl_any_of!((A, 0), (B, 1), (C, 2), (D, 3));
+    impl_any_of!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4));
+    impl_any_of!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5));
+    impl_any_of!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6));
+    impl_any_of!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7));
+}
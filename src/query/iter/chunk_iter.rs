@@ -0,0 +1,168 @@
+use crate::entity::Entity;
+use crate::query::Query;
+use core::iter::FusedIterator;
+use core::option::IntoIter as OptionIntoIter;
+
+/// Iterator over runs of items that match a query and are stored
+/// consecutively, returned as slices.
+#[must_use]
+pub enum ChunkIter<'a, G, I, E>
+where
+    G: Query,
+    I: Query,
+    E: Query,
+{
+    /// Sparse iterator, which splits the matched items into runs.
+    Sparse(SparseChunkIter<'a, G, I, E>),
+    /// Dense iterator, which returns all items in one run.
+    Dense(OptionIntoIter<(&'a [Entity], G::Slice<'a>)>),
+}
+
+impl<G, I, E> ChunkIter<'_, G, I, E>
+where
+    G: Query,
+    I: Query,
+    E: Query,
+{
+    /// Returns whether the iterator is sparse.
+    #[must_use]
+    pub const fn is_sparse(&self) -> bool {
+        matches!(self, Self::Sparse(_))
+    }
+
+    /// Returns whether the iterator is dense.
+    #[must_use]
+    pub const fn is_dense(&self) -> bool {
+        matches!(self, Self::Dense(_))
+    }
+}
+
+impl<'a, G, I, E> Iterator for ChunkIter<'a, G, I, E>
+where
+    G: Query,
+    I: Query,
+    E: Query,
+{
+    type Item = (&'a [Entity], G::Slice<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Sparse(iter) => iter.next(),
+            Self::Dense(iter) => iter.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Self::Sparse(iter) => iter.size_hint(),
+            Self::Dense(iter) => iter.size_hint(),
+        }
+    }
+}
+
+impl<G, I, E> FusedIterator for ChunkIter<'_, G, I, E>
+where
+    G: Query,
+    I: Query,
+    E: Query,
+{
+    // Empty
+}
+
+/// Sparse iterator over runs of items that match a query and are stored
+/// consecutively in all storages sliced by the query.
+#[must_use]
+pub struct SparseChunkIter<'a, G, I, E>
+where
+    G: Query,
+    I: Query,
+    E: Query,
+{
+    entities: &'a [Entity],
+    exclude_sparse: E::Sparse<'a>,
+    include_sparse: I::Sparse<'a>,
+    get_sparse: G::Sparse<'a>,
+    get_data: G::Data<'a>,
+}
+
+impl<'a, G, I, E> SparseChunkIter<'a, G, I, E>
+where
+    G: Query,
+    I: Query,
+    E: Query,
+{
+    pub(crate) fn new(
+        entities: &'a [Entity],
+        exclude_sparse: E::Sparse<'a>,
+        include_sparse: I::Sparse<'a>,
+        get_sparse: G::Sparse<'a>,
+        get_data: G::Data<'a>,
+    ) -> Self {
+        Self {
+            entities,
+            exclude_sparse,
+            include_sparse,
+            get_sparse,
+            get_data,
+        }
+    }
+
+    fn matches(&self, entity: Entity) -> bool {
+        let sparse = entity.sparse();
+
+        E::contains_none_raw(self.exclude_sparse, sparse)
+            && I::contains_all_raw(self.include_sparse, sparse)
+            && G::contains_all_raw(self.get_sparse, sparse)
+    }
+}
+
+impl<'a, G, I, E> Iterator for SparseChunkIter<'a, G, I, E>
+where
+    G: Query,
+    I: Query,
+    E: Query,
+{
+    type Item = (&'a [Entity], G::Slice<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self
+            .entities
+            .iter()
+            .position(|&entity| self.matches(entity))?;
+
+        let mut end = start + 1;
+
+        while let Some(&entity) = self.entities.get(end) {
+            let prev = self.entities[end - 1];
+
+            if !self.matches(entity) || !G::is_consecutive_raw(self.get_sparse, prev, entity) {
+                break;
+            }
+
+            end += 1;
+        }
+
+        let entities = &self.entities[start..end];
+        self.entities = &self.entities[end..];
+
+        unsafe {
+            Some((
+                entities,
+                G::slice_run_raw(self.get_sparse, self.get_data, entities),
+            ))
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.entities.len()))
+    }
+}
+
+impl<G, I, E> FusedIterator for SparseChunkIter<'_, G, I, E>
+where
+    G: Query,
+    I: Query,
+    E: Query,
+{
+    // Empty
+}
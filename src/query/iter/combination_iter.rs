@@ -0,0 +1,170 @@
+use crate::entity::Entity;
+use crate::query::{Query, QueryAll, ReadOnlyQuery};
+use alloc::vec::Vec;
+use core::array;
+use core::iter::FusedIterator;
+use core::ops::Range;
+
+/// Iterator over all combinations of `K` distinct items that match a
+/// read-only query.
+///
+/// Combinations are returned in lexicographic order of the positions of
+/// their items in the query iteration, so each unordered set of items is
+/// returned exactly once.
+#[must_use]
+pub struct CombinationIter<'a, G, const K: usize>
+where
+    G: Query,
+{
+    matched: Matched,
+    entities: &'a [Entity],
+    get_sparse: G::Sparse<'a>,
+    get_data: G::Data<'a>,
+    indexes: Option<[usize; K]>,
+}
+
+impl<'a, G, const K: usize> CombinationIter<'a, G, K>
+where
+    G: Query,
+{
+    pub(crate) fn new(
+        matched: Matched,
+        entities: &'a [Entity],
+        get_sparse: G::Sparse<'a>,
+        get_data: G::Data<'a>,
+    ) -> Self {
+        Self {
+            indexes: first_indexes(matched.len()),
+            matched,
+            entities,
+            get_sparse,
+            get_data,
+        }
+    }
+}
+
+impl<'a, G, const K: usize> Iterator for CombinationIter<'a, G, K>
+where
+    G: ReadOnlyQuery,
+{
+    type Item = [G::Item<'a>; K];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let indexes = self.indexes?;
+        self.indexes = next_indexes(indexes, self.matched.len());
+
+        Some(array::from_fn(|i| unsafe {
+            self.matched
+                .get::<G>(self.entities, self.get_sparse, self.get_data, indexes[i])
+        }))
+    }
+}
+
+impl<G, const K: usize> FusedIterator for CombinationIter<'_, G, K>
+where
+    G: ReadOnlyQuery,
+{
+    // Empty
+}
+
+/// Lending iterator over all combinations of `K` distinct items that match a
+/// query, which may give mutable access to components.
+///
+/// Items are returned by [`fetch_next`](Self::fetch_next) and borrow the
+/// iterator, so only one combination can be held at a time.
+#[must_use]
+pub struct CombinationIterMut<'a, 'w, G, I, E, const K: usize>
+where
+    G: Query,
+    I: Query,
+    E: Query,
+{
+    query: &'a mut QueryAll<'w, G, I, E>,
+    matched: Matched,
+    indexes: Option<[usize; K]>,
+}
+
+impl<'a, 'w, G, I, E, const K: usize> CombinationIterMut<'a, 'w, G, I, E, K>
+where
+    G: Query,
+    I: Query,
+    E: Query,
+{
+    pub(crate) fn new(query: &'a mut QueryAll<'w, G, I, E>, matched: Matched) -> Self {
+        Self {
+            query,
+            indexes: first_indexes(matched.len()),
+            matched,
+        }
+    }
+
+    /// Returns the next combination of items, if any.
+    pub fn fetch_next(&mut self) -> Option<[G::Item<'_>; K]> {
+        let indexes = self.indexes?;
+        self.indexes = next_indexes(indexes, self.matched.len());
+
+        let (entities, get_sparse, get_data) = self.query.item_parts();
+
+        // The items belong to distinct entities, so they don't alias.
+        Some(array::from_fn(|i| unsafe {
+            self.matched
+                .get::<G>(entities, get_sparse, get_data, indexes[i])
+        }))
+    }
+}
+
+/// Items that match a query, as iterated by combination iterators.
+pub(crate) enum Matched {
+    /// Range of dense indexes of a grouped query.
+    Dense(Range<usize>),
+    /// Entities that match a sparse query.
+    Sparse(Vec<Entity>),
+}
+
+impl Matched {
+    fn len(&self) -> usize {
+        match self {
+            Self::Dense(range) => range.len(),
+            Self::Sparse(entities) => entities.len(),
+        }
+    }
+
+    /// Returns the item at position `i`. `entities` are the dense entities of
+    /// the query.
+    unsafe fn get<'a, G>(
+        &self,
+        entities: &'a [Entity],
+        get_sparse: G::Sparse<'a>,
+        get_data: G::Data<'a>,
+        i: usize,
+    ) -> G::Item<'a>
+    where
+        G: Query,
+    {
+        match self {
+            Self::Dense(range) => {
+                let index = range.start + i;
+                G::get_dense_raw(get_data, index, *entities.get_unchecked(index))
+            }
+            Self::Sparse(matched) => {
+                let entity = *matched.get_unchecked(i);
+                G::get_sparse_raw(get_sparse, get_data, entity).unwrap_unchecked()
+            }
+        }
+    }
+}
+
+fn first_indexes<const K: usize>(len: usize) -> Option<[usize; K]> {
+    (K <= len).then(|| array::from_fn(|i| i))
+}
+
+fn next_indexes<const K: usize>(mut indexes: [usize; K], len: usize) -> Option<[usize; K]> {
+    let i = (0..K).rev().find(|&i| indexes[i] < len - K + i)?;
+    indexes[i] += 1;
+
+    for j in (i + 1)..K {
+        indexes[j] = indexes[j - 1] + 1;
+    }
+
+    Some(indexes)
+}
@@ -68,6 +68,34 @@ where
     }
 }
 
+impl<G> DoubleEndedIterator for DenseIter<'_, G>
+where
+    G: Query,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let i = self.range.next_back()?;
+
+        unsafe {
+            let entity = *self.entities.add(i).as_ref();
+            Some(G::get_dense_raw(self.get_data, i, entity))
+        }
+    }
+
+    fn rfold<B, F>(self, mut init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        for i in self.range.rev() {
+            unsafe {
+                let entity = *self.entities.add(i).as_ref();
+                init = f(init, G::get_dense_raw(self.get_data, i, entity));
+            }
+        }
+
+        init
+    }
+}
+
 impl<G> ExactSizeIterator for DenseIter<'_, G>
 where
     G: Query,
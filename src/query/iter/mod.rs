@@ -1,13 +1,24 @@
+mod chunk_iter;
+mod combination_iter;
 mod dense_iter;
+mod sorted_iter;
 mod sparse_iter;
 
+pub use self::chunk_iter::*;
+pub use self::combination_iter::*;
 pub use self::dense_iter::*;
+pub use self::sorted_iter::*;
 pub use self::sparse_iter::*;
 
 use crate::query::Query;
 use core::iter::FusedIterator;
 
 /// Sparse or dense iterator over all items that match the query.
+///
+/// The iterator does not implement [`ExactSizeIterator`] because the number
+/// of items yielded by a sparse iterator is only known after filtering every
+/// entity. When the iterator is dense, [`size_hint`](Iterator::size_hint) is
+/// exact, so collecting the items still allocates only once.
 #[must_use]
 pub enum Iter<'a, G, I, E>
 where
@@ -73,6 +84,30 @@ where
     }
 }
 
+impl<G, I, E> DoubleEndedIterator for Iter<'_, G, I, E>
+where
+    G: Query,
+    I: Query,
+    E: Query,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Sparse(iter) => iter.next_back(),
+            Self::Dense(iter) => iter.next_back(),
+        }
+    }
+
+    fn rfold<B, F>(self, init: B, f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        match self {
+            Self::Sparse(iter) => iter.rfold(init, f),
+            Self::Dense(iter) => iter.rfold(init, f),
+        }
+    }
+}
+
 impl<G, I, E> FusedIterator for Iter<'_, G, I, E>
 where
     G: Query,
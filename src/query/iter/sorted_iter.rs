@@ -0,0 +1,173 @@
+use crate::entity::Entity;
+use crate::query::{Iter, Query};
+use alloc::vec::IntoIter as VecIntoIter;
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+
+/// Iterator over all items that match a query, in a sorted order.
+#[must_use]
+pub enum SortedIter<'a, G, I, E>
+where
+    G: Query,
+    I: Query,
+    E: Query,
+{
+    /// The items were already stored in order and are iterated directly.
+    Direct(Iter<'a, G, I, E>),
+    /// The items are looked up from a sorted list of entities.
+    Lookup(LookupIter<'a, G>),
+}
+
+impl<G, I, E> SortedIter<'_, G, I, E>
+where
+    G: Query,
+    I: Query,
+    E: Query,
+{
+    /// Returns whether the items are iterated directly from the storages.
+    #[must_use]
+    pub const fn is_direct(&self) -> bool {
+        matches!(self, Self::Direct(_))
+    }
+
+    /// Returns whether the items are looked up from a sorted list of
+    /// entities.
+    #[must_use]
+    pub const fn is_lookup(&self) -> bool {
+        matches!(self, Self::Lookup(_))
+    }
+}
+
+impl<'a, G, I, E> Iterator for SortedIter<'a, G, I, E>
+where
+    G: Query,
+    I: Query,
+    E: Query,
+{
+    type Item = G::Item<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Direct(iter) => iter.next(),
+            Self::Lookup(iter) => iter.next(),
+        }
+    }
+
+    fn fold<B, F>(self, init: B, f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        match self {
+            Self::Direct(iter) => iter.fold(init, f),
+            Self::Lookup(iter) => iter.fold(init, f),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Self::Direct(iter) => iter.size_hint(),
+            Self::Lookup(iter) => iter.size_hint(),
+        }
+    }
+}
+
+impl<G, I, E> DoubleEndedIterator for SortedIter<'_, G, I, E>
+where
+    G: Query,
+    I: Query,
+    E: Query,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Direct(iter) => iter.next_back(),
+            Self::Lookup(iter) => iter.next_back(),
+        }
+    }
+}
+
+impl<G, I, E> FusedIterator for SortedIter<'_, G, I, E>
+where
+    G: Query,
+    I: Query,
+    E: Query,
+{
+    // Empty
+}
+
+/// Iterator that looks up the items of a list of entities that match a query.
+#[must_use]
+pub struct LookupIter<'a, G>
+where
+    G: Query,
+{
+    entities: VecIntoIter<Entity>,
+    get_sparse: G::Sparse<'a>,
+    get_data: G::Data<'a>,
+}
+
+impl<'a, G> LookupIter<'a, G>
+where
+    G: Query,
+{
+    /// Creates an iterator over the items of `entities`.
+    ///
+    /// # Safety
+    ///
+    /// All `entities` must match the query.
+    pub(crate) unsafe fn new(
+        entities: Vec<Entity>,
+        get_sparse: G::Sparse<'a>,
+        get_data: G::Data<'a>,
+    ) -> Self {
+        Self {
+            entities: entities.into_iter(),
+            get_sparse,
+            get_data,
+        }
+    }
+
+    unsafe fn get(&self, entity: Entity) -> G::Item<'a> {
+        G::get_sparse_raw(self.get_sparse, self.get_data, entity).unwrap_unchecked()
+    }
+}
+
+impl<'a, G> Iterator for LookupIter<'a, G>
+where
+    G: Query,
+{
+    type Item = G::Item<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let entity = self.entities.next()?;
+        unsafe { Some(self.get(entity)) }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.entities.len();
+        (len, Some(len))
+    }
+}
+
+impl<G> DoubleEndedIterator for LookupIter<'_, G>
+where
+    G: Query,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let entity = self.entities.next_back()?;
+        unsafe { Some(self.get(entity)) }
+    }
+}
+
+impl<G> ExactSizeIterator for LookupIter<'_, G>
+where
+    G: Query,
+{
+    // Empty
+}
+
+impl<G> FusedIterator for LookupIter<'_, G>
+where
+    G: Query,
+{
+    // Empty
+}
@@ -16,6 +16,7 @@ where
     include_sparse: I::Sparse<'a>,
     get_sparse: G::Sparse<'a>,
     get_data: G::Data<'a>,
+    matched: bool,
 }
 
 impl<'a, G, I, E> SparseIter<'a, G, I, E>
@@ -30,6 +31,7 @@ where
         include_sparse: I::Sparse<'a>,
         get_sparse: G::Sparse<'a>,
         get_data: G::Data<'a>,
+        matched: bool,
     ) -> Self {
         Self {
             entities: entities.iter(),
@@ -37,8 +39,30 @@ where
             include_sparse,
             get_sparse,
             get_data,
+            matched,
         }
     }
+
+    fn get(&self, entity: Entity) -> Option<G::Item<'a>> {
+        let sparse = entity.sparse();
+
+        if !E::contains_none_raw(self.exclude_sparse, sparse) {
+            return None;
+        }
+
+        if self.matched {
+            // The entity is known to match the "get" and "include" parts.
+            return unsafe {
+                Some(G::get_sparse_raw(self.get_sparse, self.get_data, entity).unwrap_unchecked())
+            };
+        }
+
+        if !I::contains_all_raw(self.include_sparse, sparse) {
+            return None;
+        }
+
+        unsafe { G::get_sparse_raw(self.get_sparse, self.get_data, entity) }
+    }
 }
 
 impl<'a, G, I, E> Iterator for SparseIter<'a, G, I, E>
@@ -51,51 +75,46 @@ where
 
     fn next(&mut self) -> Option<Self::Item> {
         loop {
-            let Some(&entity) = self.entities.next() else {
-                break None;
-            };
-
-            let sparse = entity.sparse();
-
-            if !E::contains_none_raw(self.exclude_sparse, sparse) {
-                continue;
-            }
-
-            if !I::contains_all_raw(self.include_sparse, sparse) {
-                continue;
-            }
+            let &entity = self.entities.next()?;
 
-            unsafe {
-                if let Some(item) = G::get_sparse_raw(self.get_sparse, self.get_data, entity) {
-                    break Some(item);
-                };
+            if let Some(item) = self.get(entity) {
+                break Some(item);
             }
         }
     }
 
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.entities.len()))
+    }
+
     fn fold<B, F>(self, mut init: B, mut f: F) -> B
     where
         F: FnMut(B, Self::Item) -> B,
     {
-        for &entity in self.entities {
-            let sparse = entity.sparse();
-
-            if !E::contains_none_raw(self.exclude_sparse, sparse) {
-                continue;
+        for &entity in self.entities.clone() {
+            if let Some(item) = self.get(entity) {
+                init = f(init, item);
             }
+        }
 
-            if !I::contains_all_raw(self.include_sparse, sparse) {
-                continue;
-            }
+        init
+    }
+}
+
+impl<G, I, E> DoubleEndedIterator for SparseIter<'_, G, I, E>
+where
+    G: Query,
+    I: Query,
+    E: Query,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            let &entity = self.entities.next_back()?;
 
-            unsafe {
-                if let Some(item) = G::get_sparse_raw(self.get_sparse, self.get_data, entity) {
-                    init = f(init, item);
-                };
+            if let Some(item) = self.get(entity) {
+                break Some(item);
             }
         }
-
-        init
     }
 }
 
@@ -1,24 +1,31 @@
 //! Query and iterate entities and components.
 
+mod filter;
 mod iter;
+mod prepared_query;
 mod query_all;
 mod query_one;
 mod query_part;
+mod query_plan;
 
 #[cfg(feature = "parallel")]
 mod par_iter;
 
+pub use self::filter::*;
 pub use self::iter::*;
+pub use self::prepared_query::*;
 pub use self::query_all::*;
 pub use self::query_one::*;
 pub use self::query_part::*;
+pub use self::query_plan::*;
 
 #[cfg(feature = "parallel")]
 pub use self::par_iter::*;
 
-use crate::component::QueryGroupInfo;
+use crate::component::{ComponentData, QueryGroupInfo};
 use crate::entity::Entity;
 use crate::World;
+use alloc::vec::Vec;
 use core::mem::MaybeUninit;
 use core::ops::Range;
 use core::ptr;
@@ -45,14 +52,56 @@ pub unsafe trait Query {
     /// Data used for sparse and dense iteration.
     type Data<'a>: Copy;
 
+    /// Storage lookups cached by [`PreparedQuery`].
+    type State: Copy;
+
+    /// Whether any part of the query can only be evaluated per entity, which
+    /// prevents the query from being iterated densely.
+    const SPARSE_ONLY: bool = false;
+
+    /// Whether any part of the query only matches entities that lack some
+    /// components, like [`Not`].
+    const EXCLUDES: bool = false;
+
     /// Borrows a view from the `world`.
     #[must_use]
     fn borrow(world: &World) -> Self::View<'_>;
 
+    /// Looks up the storages borrowed by the query.
+    #[must_use]
+    fn prepare(world: &World) -> Self::State;
+
+    /// Borrows a view from the `world` using the storages looked up by
+    /// [`prepare`](Self::prepare).
+    ///
+    /// # Safety
+    ///
+    /// `state` must have been returned by `prepare` on the same `world`, while
+    /// it had the same layout generation.
+    #[must_use]
+    unsafe fn borrow_prepared(world: &World, state: Self::State) -> Self::View<'_>;
+
     /// Borrows a view from the `world` along with grouping information.
     #[must_use]
     fn borrow_with_group_info(world: &World) -> (Self::View<'_>, Option<QueryGroupInfo>);
 
+    /// Returns the grouping information of the components the query requires
+    /// matched entities to lack, or `None` if they can't be grouped.
+    #[must_use]
+    fn exclude_group_info(_world: &World) -> Option<QueryGroupInfo> {
+        Some(QueryGroupInfo::Empty)
+    }
+
+    /// Adds the component types required by the query to `components`.
+    fn add_required_components(components: &mut Vec<ComponentData>);
+
+    /// Returns the component type of the storage whose entities are returned
+    /// by [`split_filter_parts`](Self::split_filter_parts), if any.
+    #[must_use]
+    fn driver_component(_view: &Self::View<'_>) -> Option<ComponentData> {
+        None
+    }
+
     /// Returns whether `entity` is present in all parts of the `view`.
     #[must_use]
     fn contains_all(view: &Self::View<'_>, entity: Entity) -> bool;
@@ -88,6 +137,11 @@ pub unsafe trait Query {
     #[must_use]
     fn contains_none_raw(sparse: Self::Sparse<'_>, sparse_index: usize) -> bool;
 
+    /// Returns whether the items of `entity` are stored right after the items
+    /// of `prev` in all storages sliced by the query.
+    #[must_use]
+    fn is_consecutive_raw(sparse: Self::Sparse<'_>, prev: Entity, entity: Entity) -> bool;
+
     /// Returns the item mapped to `entity`, if any.
     #[must_use]
     unsafe fn get_sparse_raw<'a>(
@@ -107,6 +161,36 @@ pub unsafe trait Query {
         entities: &'a [Entity],
         range: Range<usize>,
     ) -> Self::Slice<'a>;
+
+    /// Slices the data of a run of `entities` whose items are stored
+    /// consecutively in all storages sliced by the query.
+    #[must_use]
+    unsafe fn slice_run_raw<'a>(
+        sparse: Self::Sparse<'a>,
+        data: Self::Data<'a>,
+        entities: &'a [Entity],
+    ) -> Self::Slice<'a>;
+}
+
+/// Marker for queries that never give mutable access to components, so their
+/// items can be held for multiple entities at once.
+///
+/// # Safety
+///
+/// Items returned by the query must be able to alias each other.
+pub unsafe trait ReadOnlyQuery: Query {
+    // Empty
+}
+
+unsafe impl ReadOnlyQuery for () {
+    // Empty
+}
+
+unsafe impl<Q> ReadOnlyQuery for Q
+where
+    Q: ReadOnlyQueryPart,
+{
+    // Empty
 }
 
 #[allow(clippy::unused_unit)]
@@ -116,17 +200,33 @@ unsafe impl Query for () {
     type Slice<'a> = ();
     type Sparse<'a> = ();
     type Data<'a> = ();
+    type State = ();
 
     #[inline]
     fn borrow(_world: &World) -> Self::View<'_> {
         // Empty
     }
 
+    #[inline]
+    fn prepare(_world: &World) -> Self::State {
+        // Empty
+    }
+
+    #[inline]
+    unsafe fn borrow_prepared(_world: &World, _state: Self::State) -> Self::View<'_> {
+        // Empty
+    }
+
     #[inline]
     fn borrow_with_group_info(_world: &World) -> (Self::View<'_>, Option<QueryGroupInfo>) {
         ((), Some(QueryGroupInfo::Empty))
     }
 
+    #[inline]
+    fn add_required_components(_components: &mut Vec<ComponentData>) {
+        // Empty
+    }
+
     #[inline]
     fn contains_all(_view: &Self::View<'_>, _entity: Entity) -> bool {
         true
@@ -171,6 +271,11 @@ unsafe impl Query for () {
         true
     }
 
+    #[inline]
+    fn is_consecutive_raw(_sparse: Self::Sparse<'_>, _prev: Entity, _entity: Entity) -> bool {
+        true
+    }
+
     #[inline]
     unsafe fn get_sparse_raw<'a>(
         _sparse: Self::Sparse<'a>,
@@ -197,6 +302,15 @@ unsafe impl Query for () {
     ) -> Self::Slice<'a> {
         // Empty
     }
+
+    #[inline]
+    unsafe fn slice_run_raw<'a>(
+        _sparse: Self::Sparse<'a>,
+        _data: Self::Data<'a>,
+        _entities: &'a [Entity],
+    ) -> Self::Slice<'a> {
+        // Empty
+    }
 }
 
 unsafe impl<Q> Query for Q
@@ -208,15 +322,44 @@ where
     type Slice<'a> = <Q as QueryPart>::Slice<'a>;
     type Sparse<'a> = <Q as QueryPart>::Sparse<'a>;
     type Data<'a> = <Q as QueryPart>::Data<'a>;
+    type State = <Q as QueryPart>::State;
 
     fn borrow(world: &World) -> Self::View<'_> {
         <Q as QueryPart>::borrow(world)
     }
 
+    fn prepare(world: &World) -> Self::State {
+        <Q as QueryPart>::prepare(world)
+    }
+
+    unsafe fn borrow_prepared(world: &World, state: Self::State) -> Self::View<'_> {
+        <Q as QueryPart>::borrow_prepared(world, state)
+    }
+
+    const SPARSE_ONLY: bool = <Q as QueryPart>::SPARSE_ONLY;
+
+    const EXCLUDES: bool = <Q as QueryPart>::EXCLUDES;
+
     fn borrow_with_group_info(world: &World) -> (Self::View<'_>, Option<QueryGroupInfo>) {
         let (view, info) = <Q as QueryPart>::borrow_with_group_info(world);
-        let info = info.map_or(QueryGroupInfo::Empty, QueryGroupInfo::One);
-        (view, Some(info))
+
+        if Self::SPARSE_ONLY {
+            return (view, None);
+        }
+
+        (view, info)
+    }
+
+    fn exclude_group_info(world: &World) -> Option<QueryGroupInfo> {
+        <Q as QueryPart>::exclude_group_info(world)
+    }
+
+    fn add_required_components(components: &mut Vec<ComponentData>) {
+        <Q as QueryPart>::add_required_components(components);
+    }
+
+    fn driver_component(view: &Self::View<'_>) -> Option<ComponentData> {
+        <Q as QueryPart>::driver_component(view)
     }
 
     fn contains_all(view: &Self::View<'_>, entity: Entity) -> bool {
@@ -256,6 +399,16 @@ where
         !<Q as QueryPart>::contains_raw(sparse, sparse_index)
     }
 
+    fn is_consecutive_raw(sparse: Self::Sparse<'_>, prev: Entity, entity: Entity) -> bool {
+        let prev = <Q as QueryPart>::get_dense_index_raw(sparse, prev);
+        let next = <Q as QueryPart>::get_dense_index_raw(sparse, entity);
+
+        match (prev, next) {
+            (Some(prev), Some(next)) => prev + 1 == next,
+            _ => true,
+        }
+    }
+
     unsafe fn get_sparse_raw<'a>(
         sparse: Self::Sparse<'_>,
         data: Self::Data<'a>,
@@ -276,6 +429,19 @@ where
     ) -> Self::Slice<'a> {
         <Q as QueryPart>::slice_raw(data, entities, range)
     }
+
+    unsafe fn slice_run_raw<'a>(
+        sparse: Self::Sparse<'a>,
+        data: Self::Data<'a>,
+        entities: &'a [Entity],
+    ) -> Self::Slice<'a> {
+        let start = entities
+            .first()
+            .and_then(|&entity| <Q as QueryPart>::get_dense_index_raw(sparse, entity))
+            .unwrap_or(0);
+
+        <Q as QueryPart>::slice_raw(data, entities, start..(start + entities.len()))
+    }
 }
 
 macro_rules! impl_query {
@@ -289,21 +455,36 @@ macro_rules! impl_query {
             type Slice<'a> = ($($Ty::Slice<'a>,)+);
             type Sparse<'a> = ($($Ty::Sparse<'a>,)+);
             type Data<'a> = ($($Ty::Data<'a>,)+);
+            type State = ($($Ty::State,)+);
 
             fn borrow(world: &World) -> Self::View<'_> {
                 ($($Ty::borrow(world),)+)
             }
 
+            fn prepare(world: &World) -> Self::State {
+                ($($Ty::prepare(world),)+)
+            }
+
+            unsafe fn borrow_prepared(world: &World, state: Self::State) -> Self::View<'_> {
+                ($($Ty::borrow_prepared(world, state.$idx),)+)
+            }
+
+            const SPARSE_ONLY: bool = $($Ty::SPARSE_ONLY)||+;
+
+            const EXCLUDES: bool = $($Ty::EXCLUDES)||+;
+
             fn borrow_with_group_info(world: &World) -> (Self::View<'_>, Option<QueryGroupInfo>) {
                 let view_and_group_info = ($($Ty::borrow_with_group_info(world),)+);
 
                 let get_group_info = || -> Option<QueryGroupInfo> {
+                    if Self::SPARSE_ONLY {
+                        return None;
+                    }
+
                     let mut group_info = QueryGroupInfo::Empty;
 
                     $(
-                        if let Some(info) = &view_and_group_info.$idx.1 {
-                            group_info = group_info.add_view(info)?;
-                        }
+                        group_info = group_info.add_query(view_and_group_info.$idx.1.as_ref()?)?;
                     )+
 
                     Some(group_info)
@@ -315,6 +496,36 @@ macro_rules! impl_query {
                 )
             }
 
+            fn exclude_group_info(world: &World) -> Option<QueryGroupInfo> {
+                let mut group_info = QueryGroupInfo::Empty;
+
+                $(
+                    if $Ty::EXCLUDES {
+                        group_info = group_info.add_exclusion(&$Ty::exclude_group_info(world)?)?;
+                    }
+                )+
+
+                Some(group_info)
+            }
+
+            fn add_required_components(components: &mut Vec<ComponentData>) {
+                $($Ty::add_required_components(components);)+
+            }
+
+            fn driver_component(view: &Self::View<'_>) -> Option<ComponentData> {
+                let mut driver = Option::<(&[Entity], Option<ComponentData>)>::None;
+
+                $(
+                    if let (Some(view_entities), _) = $Ty::split_filter_parts(&view.$idx) {
+                        if driver.map_or(true, |(entities, _)| view_entities.len() < entities.len()) {
+                            driver = Some((view_entities, $Ty::driver_component(&view.$idx)));
+                        }
+                    }
+                )+
+
+                driver.and_then(|(_, component)| component)
+            }
+
             fn contains_all(view: &Self::View<'_>, entity: Entity) -> bool {
                 $($Ty::contains(&view.$idx, entity))&&+
             }
@@ -410,6 +621,12 @@ macro_rules! impl_query {
                 $(!$Ty::contains_raw(sparse.$idx, sparse_index))&&+
             }
 
+            fn is_consecutive_raw(sparse: Self::Sparse<'_>, prev: Entity, entity: Entity) -> bool {
+                $(
+                    <$Ty as Query>::is_consecutive_raw(sparse.$idx, prev, entity)
+                )&&+
+            }
+
             unsafe fn get_sparse_raw<'a>(
                 sparse: Self::Sparse<'a>,
                 data: Self::Data<'a>,
@@ -434,6 +651,21 @@ macro_rules! impl_query {
             ) -> Self::Slice<'a> {
                ($($Ty::slice_raw(data.$idx, entities, range.clone()),)+)
             }
+
+            unsafe fn slice_run_raw<'a>(
+                sparse: Self::Sparse<'a>,
+                data: Self::Data<'a>,
+                entities: &'a [Entity],
+            ) -> Self::Slice<'a> {
+                ($(<$Ty as Query>::slice_run_raw(sparse.$idx, data.$idx, entities),)+)
+            }
+        }
+
+        unsafe impl<$($Ty),+> ReadOnlyQuery for ($($Ty,)+)
+        where
+            $($Ty: ReadOnlyQueryPart,)+
+        {
+            // Empty
         }
     };
 }
@@ -458,4 +690,36 @@ mod impls {
     impl_query!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7), (I, 8), (J, 9), (K, 10), (L, 11), (M, 12), (N, 13));
     impl_query!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7), (I, 8), (J, 9), (K, 10), (L, 11), (M, 12), (N, 13), (O, 14));
     impl_query!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7), (I, 8), (J, 9), (K, 10), (L, 11), (M, 12), (N, 13), (O, 14), (P, 15));
+    #[cfg(feature = "wide-groups")]
+    impl_query!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7), (I, 8), (J, 9), (K, 10), (L, 11), (M, 12), (N, 13), (O, 14), (P, 15), (Q, 16));
+    #[cfg(feature = "wide-groups")]
+    impl_query!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7), (I, 8), (J, 9), (K, 10), (L, 11), (M, 12), (N, 13), (O, 14), (P, 15), (Q, 16), (R, 17));
+    #[cfg(feature = "wide-groups")]
+    impl_query!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7), (I, 8), (J, 9), (K, 10), (L, 11), (M, 12), (N, 13), (O, 14), (P, 15), (Q, 16), (R, 17), (S, 18));
+    #[cfg(feature = "wide-groups")]
+    impl_query!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7), (I, 8), (J, 9), (K, 10), (L, 11), (M, 12), (N, 13), (O, 14), (P, 15), (Q, 16), (R, 17), (S, 18), (T, 19));
+    #[cfg(feature = "wide-groups")]
+    impl_query!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7), (I, 8), (J, 9), (K, 10), (L, 11), (M, 12), (N, 13), (O, 14), (P, 15), (Q, 16), (R, 17), (S, 18), (T, 19), (U, 20));
+    #[cfg(feature = "wide-groups")]
+    impl_query!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7), (I, 8), (J, 9), (K, 10), (L, 11), (M, 12), (N, 13), (O, 14), (P, 15), (Q, 16), (R, 17), (S, 18), (T, 19), (U, 20), (V, 21));
+    #[cfg(feature = "wide-groups")]
+    impl_query!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7), (I, 8), (J, 9), (K, 10), (L, 11), (M, 12), (N, 13), (O, 14), (P, 15), (Q, 16), (R, 17), (S, 18), (T, 19), (U, 20), (V, 21), (W, 22));
+    #[cfg(feature = "wide-groups")]
+    impl_query!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7), (I, 8), (J, 9), (K, 10), (L, 11), (M, 12), (N, 13), (O, 14), (P, 15), (Q, 16), (R, 17), (S, 18), (T, 19), (U, 20), (V, 21), (W, 22), (X, 23));
+    #[cfg(feature = "wide-groups")]
+    impl_query!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7), (I, 8), (J, 9), (K, 10), (L, 11), (M, 12), (N, 13), (O, 14), (P, 15), (Q, 16), (R, 17), (S, 18), (T, 19), (U, 20), (V, 21), (W, 22), (X, 23), (Y, 24));
+    #[cfg(feature = "wide-groups")]
+    impl_query!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7), (I, 8), (J, 9), (K, 10), (L, 11), (M, 12), (N, 13), (O, 14), (P, 15), (Q, 16), (R, 17), (S, 18), (T, 19), (U, 20), (V, 21), (W, 22), (X, 23), (Y, 24), (Z, 25));
+    #[cfg(feature = "wide-groups")]
+    impl_query!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7), (I, 8), (J, 9), (K, 10), (L, 11), (M, 12), (N, 13), (O, 14), (P, 15), (Q, 16), (R, 17), (S, 18), (T, 19), (U, 20), (V, 21), (W, 22), (X, 23), (Y, 24), (Z, 25), (AA, 26));
+    #[cfg(feature = "wide-groups")]
+    impl_query!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7), (I, 8), (J, 9), (K, 10), (L, 11), (M, 12), (N, 13), (O, 14), (P, 15), (Q, 16), (R, 17), (S, 18), (T, 19), (U, 20), (V, 21), (W, 22), (X, 23), (Y, 24), (Z, 25), (AA, 26), (AB, 27));
+    #[cfg(feature = "wide-groups")]
+    impl_query!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7), (I, 8), (J, 9), (K, 10), (L, 11), (M, 12), (N, 13), (O, 14), (P, 15), (Q, 16), (R, 17), (S, 18), (T, 19), (U, 20), (V, 21), (W, 22), (X, 23), (Y, 24), (Z, 25), (AA, 26), (AB, 27), (AC, 28));
+    #[cfg(feature = "wide-groups")]
+    impl_query!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7), (I, 8), (J, 9), (K, 10), (L, 11), (M, 12), (N, 13), (O, 14), (P, 15), (Q, 16), (R, 17), (S, 18), (T, 19), (U, 20), (V, 21), (W, 22), (X, 23), (Y, 24), (Z, 25), (AA, 26), (AB, 27), (AC, 28), (AD, 29));
+    #[cfg(feature = "wide-groups")]
+    impl_query!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7), (I, 8), (J, 9), (K, 10), (L, 11), (M, 12), (N, 13), (O, 14), (P, 15), (Q, 16), (R, 17), (S, 18), (T, 19), (U, 20), (V, 21), (W, 22), (X, 23), (Y, 24), (Z, 25), (AA, 26), (AB, 27), (AC, 28), (AD, 29), (AE, 30));
+    #[cfg(feature = "wide-groups")]
+    impl_query!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6), (H, 7), (I, 8), (J, 9), (K, 10), (L, 11), (M, 12), (N, 13), (O, 14), (P, 15), (Q, 16), (R, 17), (S, 18), (T, 19), (U, 20), (V, 21), (W, 22), (X, 23), (Y, 24), (Z, 25), (AA, 26), (AB, 27), (AC, 28), (AD, 29), (AE, 30), (AF, 31));
 }
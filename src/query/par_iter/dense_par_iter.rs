@@ -14,6 +14,8 @@ where
     range: Range<usize>,
     entities: NonNull<Entity>,
     get_data: G::Data<'a>,
+    min_len: usize,
+    max_len: usize,
 }
 
 impl<'a, G> DenseParIter<'a, G>
@@ -31,9 +33,19 @@ where
             range,
             entities,
             get_data,
+            min_len: 1,
+            max_len: usize::MAX,
         }
     }
 
+    pub(crate) fn set_min_len(&mut self, min_len: usize) {
+        self.min_len = min_len;
+    }
+
+    pub(crate) fn set_max_len(&mut self, max_len: usize) {
+        self.max_len = max_len;
+    }
+
     #[must_use]
     unsafe fn get(&self, index: usize) -> G::Item<'a> {
         let entity = *self.entities.add(index).as_ref();
@@ -68,6 +80,8 @@ where
         self.range
             .clone()
             .into_par_iter()
+            .with_min_len(self.min_len)
+            .with_max_len(self.max_len)
             .map(|i| unsafe { self.get(i) })
             .drive_unindexed(consumer)
     }
@@ -77,7 +91,7 @@ where
     }
 }
 
-impl<'a, G> IndexedParallelIterator for DenseParIter<'a, G>
+impl<G> IndexedParallelIterator for DenseParIter<'_, G>
 where
     G: Query,
 {
@@ -88,6 +102,8 @@ where
         self.range
             .clone()
             .into_par_iter()
+            .with_min_len(self.min_len)
+            .with_max_len(self.max_len)
             .map(|i| unsafe { self.get(i) })
             .drive(consumer)
     }
@@ -103,6 +119,8 @@ where
         self.range
             .clone()
             .into_par_iter()
+            .with_min_len(self.min_len)
+            .with_max_len(self.max_len)
             .map(|i| unsafe { self.get(i) })
             .with_producer(callback)
     }
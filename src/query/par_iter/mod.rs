@@ -22,7 +22,7 @@ where
     Dense(DenseParIter<'a, G>),
 }
 
-impl<'a, G, I, E> ParIter<'a, G, I, E>
+impl<G, I, E> ParIter<'_, G, I, E>
 where
     G: Query,
     I: Query,
@@ -39,6 +39,30 @@ where
     pub const fn is_dense(&self) -> bool {
         matches!(self, Self::Dense(_))
     }
+
+    /// Sets the minimum number of items processed by each parallel task. For
+    /// sparse iterators, this is the number of entities checked for a match.
+    ///
+    /// Raising it reduces scheduling overhead when the work per item is tiny.
+    pub fn with_min_len(mut self, min_len: usize) -> Self {
+        match &mut self {
+            Self::Sparse(iter) => iter.set_min_len(min_len),
+            Self::Dense(iter) => iter.set_min_len(min_len),
+        }
+
+        self
+    }
+
+    /// Sets the maximum number of items processed by each parallel task. For
+    /// sparse iterators, this is the number of entities checked for a match.
+    pub fn with_max_len(mut self, max_len: usize) -> Self {
+        match &mut self {
+            Self::Sparse(iter) => iter.set_max_len(max_len),
+            Self::Dense(iter) => iter.set_max_len(max_len),
+        }
+
+        self
+    }
 }
 
 impl<'a, G, I, E> ParallelIterator for ParIter<'a, G, I, E>
@@ -1,7 +1,7 @@
 use crate::entity::Entity;
 use crate::query::Query;
 use rayon::iter::plumbing::UnindexedConsumer;
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
 /// Sparse parallel iterator over all items that match a query.
 #[must_use]
@@ -16,6 +16,9 @@ where
     include_sparse: I::Sparse<'a>,
     get_sparse: G::Sparse<'a>,
     get_data: G::Data<'a>,
+    matched: bool,
+    min_len: usize,
+    max_len: usize,
 }
 
 impl<'a, G, I, E> SparseParIter<'a, G, I, E>
@@ -30,6 +33,7 @@ where
         include_sparse: I::Sparse<'a>,
         get_sparse: G::Sparse<'a>,
         get_data: G::Data<'a>,
+        matched: bool,
     ) -> Self {
         Self {
             entities,
@@ -37,9 +41,20 @@ where
             include_sparse,
             get_sparse,
             get_data,
+            matched,
+            min_len: 1,
+            max_len: usize::MAX,
         }
     }
 
+    pub(crate) fn set_min_len(&mut self, min_len: usize) {
+        self.min_len = min_len;
+    }
+
+    pub(crate) fn set_max_len(&mut self, max_len: usize) {
+        self.max_len = max_len;
+    }
+
     #[must_use]
     unsafe fn get(&self, entity: Entity) -> Option<G::Item<'a>> {
         let sparse = entity.sparse();
@@ -48,6 +63,13 @@ where
             return None;
         }
 
+        if self.matched {
+            // The entity is known to match the "get" and "include" parts.
+            return unsafe {
+                Some(G::get_sparse_raw(self.get_sparse, self.get_data, entity).unwrap_unchecked())
+            };
+        }
+
         if !I::contains_all_raw(self.include_sparse, sparse) {
             return None;
         }
@@ -88,6 +110,8 @@ where
     {
         self.entities
             .par_iter()
+            .with_min_len(self.min_len)
+            .with_max_len(self.max_len)
             .flat_map(|&entity| unsafe { self.get(entity) })
             .drive_unindexed(consumer)
     }
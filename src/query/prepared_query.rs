@@ -0,0 +1,62 @@
+use crate::component::{GroupBounds, NonOwningMatch};
+use crate::query::{DenseRejection, Query, QueryAll, QueryGroupInfo};
+use crate::World;
+
+/// Query that caches its storage lookups and grouping information, so it can
+/// be run repeatedly with less overhead than [`World::query_all`].
+///
+/// Running the query only borrows the storages and reads the lengths of the
+/// groups it iterates. The cached data is refreshed automatically when the
+/// query is run on a different world or after the group layout changed.
+///
+/// Created with [`QueryAll::prepare`] or [`PreparedQuery::new`].
+pub struct PreparedQuery<G, I = (), E = ()>
+where
+    G: Query,
+    I: Query,
+    E: Query,
+{
+    pub(crate) world_id: u64,
+    pub(crate) generation: u64,
+    pub(crate) get: G::State,
+    pub(crate) include: I::State,
+    pub(crate) exclude: E::State,
+    pub(crate) get_info: Option<QueryGroupInfo>,
+    pub(crate) include_info: Option<QueryGroupInfo>,
+    pub(crate) exclude_info: Option<QueryGroupInfo>,
+    pub(crate) bounds: Result<GroupBounds, DenseRejection>,
+    pub(crate) non_owning: NonOwningMatch,
+}
+
+impl<G, I, E> PreparedQuery<G, I, E>
+where
+    G: Query,
+    I: Query,
+    E: Query,
+{
+    /// Prepares a query that gets `G`, includes `I` and excludes `E` on the
+    /// given `world`.
+    #[must_use]
+    pub fn new(world: &World) -> Self {
+        QueryAll::<G, I, E>::new_filtered(world).prepare()
+    }
+
+    /// Borrows the query from the `world`, preparing it again first if the
+    /// cached data is no longer valid for it.
+    pub fn run<'a>(&mut self, world: &'a World) -> QueryAll<'a, G, I, E> {
+        if !self.is_prepared_for(world) {
+            let query = QueryAll::new_filtered(world);
+            *self = query.prepare();
+            return query;
+        }
+
+        unsafe { QueryAll::new_prepared(world, self) }
+    }
+
+    /// Returns whether the cached data is valid for the current layout of the
+    /// `world`.
+    #[must_use]
+    pub fn is_prepared_for(&self, world: &World) -> bool {
+        self.world_id == world.components.id && self.generation == world.components.generation
+    }
+}
@@ -1,5 +1,13 @@
-use crate::query::{DenseIter, Iter, Query, QueryGroupInfo, SparseIter};
+use crate::component::{GroupBounds, NonOwningMatch};
+use crate::entity::Entity;
+use crate::query::{
+    ChunkIter, CombinationIter, CombinationIterMut, DenseIter, DenseRejection, Iter, LookupIter,
+    Matched, PreparedQuery, Query, QueryGroupInfo, QueryPlan, ReadOnlyQuery, SortedIter,
+    SparseChunkIter, SparseDriver, SparseIter,
+};
 use crate::World;
+use alloc::vec::Vec;
+use core::fmt;
 use core::ops::Range;
 
 #[cfg(feature = "parallel")]
@@ -23,6 +31,10 @@ where
     get_info: Option<QueryGroupInfo>,
     include_info: Option<QueryGroupInfo>,
     exclude_info: Option<QueryGroupInfo>,
+    /// Group bounds cached by a prepared query.
+    bounds: Option<Result<GroupBounds, DenseRejection>>,
+    /// Non-owning groups that can drive the iteration of the query.
+    non_owning: NonOwningMatch,
 }
 
 impl<'a, G> QueryAll<'a, G, (), ()>
@@ -39,7 +51,53 @@ where
             exclude: (),
             get_info,
             include_info: Some(QueryGroupInfo::Empty),
-            exclude_info: Some(QueryGroupInfo::Empty),
+            exclude_info: add_exclusions::<G>(world, Some(QueryGroupInfo::Empty)),
+            bounds: None,
+            non_owning: Self::non_owning_match(world),
+        }
+    }
+}
+
+impl<'a, G, I, E> QueryAll<'a, G, I, E>
+where
+    G: Query,
+    I: Query,
+    E: Query,
+{
+    pub(crate) fn new_filtered(world: &'a World) -> Self {
+        let (get, get_info) = G::borrow_with_group_info(world);
+        let (include, include_info) = I::borrow_with_group_info(world);
+        let (exclude, exclude_info) = E::borrow_with_group_info(world);
+        let exclude_info = exclude_info.filter(|_| !E::EXCLUDES);
+        let exclude_info = add_exclusions::<G>(world, exclude_info);
+        let exclude_info = add_exclusions::<I>(world, exclude_info);
+
+        Self {
+            world,
+            get,
+            include,
+            exclude,
+            get_info,
+            include_info,
+            exclude_info,
+            bounds: None,
+            non_owning: Self::non_owning_match(world),
+        }
+    }
+
+    /// Borrows the query using the lookups cached by `prepared`, which must be
+    /// prepared for the `world` in its current generation.
+    pub(crate) unsafe fn new_prepared(world: &'a World, prepared: &PreparedQuery<G, I, E>) -> Self {
+        Self {
+            world,
+            get: G::borrow_prepared(world, prepared.get),
+            include: I::borrow_prepared(world, prepared.include),
+            exclude: E::borrow_prepared(world, prepared.exclude),
+            get_info: prepared.get_info,
+            include_info: prepared.include_info,
+            exclude_info: prepared.exclude_info,
+            bounds: Some(prepared.bounds),
+            non_owning: prepared.non_owning,
         }
     }
 }
@@ -63,7 +121,9 @@ where
             exclude: self.exclude,
             get_info: self.get_info,
             include_info,
-            exclude_info: self.exclude_info,
+            exclude_info: add_exclusions::<I>(self.world, self.exclude_info),
+            bounds: None,
+            non_owning: QueryAll::<G, I, E>::non_owning_match(self.world),
         }
     }
 }
@@ -80,6 +140,11 @@ where
     {
         let (exclude, exclude_info) = E::borrow_with_group_info(self.world);
 
+        let exclude_info = self
+            .exclude_info
+            .zip(exclude_info.filter(|_| !E::EXCLUDES))
+            .and_then(|(info, exclude_info)| info.add_exclusion(&exclude_info));
+
         QueryAll {
             world: self.world,
             get: self.get,
@@ -88,11 +153,13 @@ where
             get_info: self.get_info,
             include_info: self.include_info,
             exclude_info,
+            bounds: None,
+            non_owning: self.non_owning,
         }
     }
 }
 
-impl<G, I, E> QueryAll<'_, G, I, E>
+impl<'a, G, I, E> QueryAll<'a, G, I, E>
 where
     G: Query,
     I: Query,
@@ -100,7 +167,18 @@ where
 {
     /// Returns an iterator over all items that match the query.
     pub fn iter(&mut self) -> Iter<'_, G, I, E> {
-        if let Some(range) = self.get_group_range() {
+        self.iter_inner(true)
+    }
+
+    /// Returns an iterator over all items that match the query, recording the
+    /// run in the profiler if `record` is `true`.
+    #[cfg_attr(not(feature = "std"), allow(unused_variables))]
+    fn iter_inner(&mut self, record: bool) -> Iter<'_, G, I, E> {
+        if let Ok(range) = self.get_group_range() {
+            #[cfg(feature = "std")]
+            if record {
+                self.record(range.len());
+            }
             let (get_entities, get_data) = G::split_dense_parts(&self.get);
             let (include_entities, _) = I::split_filter_parts(&self.include);
             let entities = get_entities.or(include_entities).unwrap();
@@ -110,18 +188,11 @@ where
             let (include_entities, include_sparse) = I::split_filter_parts(&self.include);
             let (_, exclude_sparse) = E::split_filter_parts(&self.exclude);
 
-            let entities = match (get_entities, include_entities) {
-                (Some(get_entities), Some(include_entities)) => {
-                    if get_entities.len() <= include_entities.len() {
-                        get_entities
-                    } else {
-                        include_entities
-                    }
-                }
-                (Some(get_entities), None) => get_entities,
-                (None, Some(include_entities)) => include_entities,
-                (None, None) => &[],
-            };
+            let (entities, matched) = self.sparse_iter_entities(get_entities, include_entities);
+            #[cfg(feature = "std")]
+            if record {
+                self.record(entities.len());
+            }
 
             Iter::Sparse(SparseIter::new(
                 entities,
@@ -129,10 +200,134 @@ where
                 include_sparse,
                 get_sparse,
                 get_data,
+                matched,
             ))
         }
     }
 
+    /// Returns an iterator over all items that match the query, in ascending
+    /// order of their entities.
+    ///
+    /// If the storages already hold the items in order, they are iterated
+    /// directly. Otherwise, the matched entities are collected and sorted
+    /// first.
+    pub fn iter_sorted_by_entity(&mut self) -> SortedIter<'_, G, I, E> {
+        let (entities, _) = self.driving_entities();
+
+        if entities.windows(2).all(|pair| pair[0] <= pair[1]) {
+            return SortedIter::Direct(self.iter());
+        }
+
+        let mut entities = Vec::new();
+        self.visit_matched(|entity, _| {
+            entities.push(entity);
+            true
+        });
+
+        entities.sort_unstable();
+        self.lookup(entities)
+    }
+
+    /// Returns an iterator over all items that match the query, in ascending
+    /// order of the keys returned by `f`. Items with equal keys are returned
+    /// in ascending order of their entities.
+    ///
+    /// The matched items are visited once to compute their keys, calling `f`
+    /// once per item and checking their order along the way. If the keys are
+    /// already in order, the storages are iterated directly. Otherwise, the
+    /// keys are sorted first. Either way, the run is recorded once.
+    pub fn iter_sorted_by_key<K, F>(&mut self, mut f: F) -> SortedIter<'_, G, I, E>
+    where
+        K: Ord,
+        F: FnMut(&G::Item<'_>) -> K,
+    {
+        let mut keys = Vec::<(K, Entity)>::new();
+        let mut sorted = true;
+
+        self.visit_matched(|entity, item| {
+            let key = (f(&item), entity);
+
+            if let Some(last) = keys.last() {
+                sorted &= *last <= key;
+            }
+
+            keys.push(key);
+            true
+        });
+
+        #[cfg(feature = "std")]
+        self.record(keys.len());
+
+        if sorted {
+            return SortedIter::Direct(self.iter_inner(false));
+        }
+
+        keys.sort_unstable();
+        self.lookup_inner(keys.into_iter().map(|(_, entity)| entity).collect())
+    }
+
+    /// Returns the number of items that match the query.
+    ///
+    /// Grouped queries read the length of their group. Other queries check the
+    /// entities that would drive their iteration, without building items.
+    #[must_use]
+    pub fn count(&self) -> usize {
+        match self.driving_entities() {
+            (entities, false) => entities.len(),
+            (_, true) => self.entities().count(),
+        }
+    }
+
+    /// Returns whether no items match the query.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entities().next().is_none()
+    }
+
+    /// Returns an iterator over the entities that match the query, in
+    /// iteration order, without building their items.
+    pub fn entities(&self) -> impl Iterator<Item = Entity> + '_ {
+        let (entities, check) = self.driving_entities();
+        let (_, get_sparse) = G::split_filter_parts(&self.get);
+        let (_, include_sparse) = I::split_filter_parts(&self.include);
+        let (_, exclude_sparse) = E::split_filter_parts(&self.exclude);
+
+        entities.iter().copied().filter(move |entity| {
+            if !check {
+                return true;
+            }
+
+            let sparse = entity.sparse();
+
+            E::contains_none_raw(exclude_sparse, sparse)
+                && I::contains_all_raw(include_sparse, sparse)
+                && G::contains_all_raw(get_sparse, sparse)
+        })
+    }
+
+    /// Returns the first item that matches the query, in iteration order, if
+    /// any.
+    #[must_use]
+    pub fn first(&mut self) -> Option<G::Item<'_>> {
+        let entity = self.entities().next()?;
+        unsafe { Some(self.get_matched(entity)) }
+    }
+
+    /// Returns the only item that matches the query, or an error if there are
+    /// no items or multiple items. Useful for singleton entities, like the
+    /// player or the camera.
+    pub fn single(&mut self) -> Result<G::Item<'_>, SingleError> {
+        let mut entities = self.entities();
+        let entity = entities.next().ok_or(SingleError::NoMatches)?;
+
+        if entities.next().is_some() {
+            return Err(SingleError::MultipleMatches);
+        }
+
+        drop(entities);
+        unsafe { Ok(self.get_matched(entity)) }
+    }
+
     /// Calls `f` for all items that match the query.
     pub fn for_each<F>(&mut self, f: F)
     where
@@ -144,7 +339,9 @@ where
     /// Returns a parallel iterator over all items that match the query.
     #[cfg(feature = "parallel")]
     pub fn par_iter(&mut self) -> ParIter<'_, G, I, E> {
-        if let Some(range) = self.get_group_range() {
+        if let Ok(range) = self.get_group_range() {
+            #[cfg(feature = "std")]
+            self.record(range.len());
             let (get_entities, get_data) = G::split_dense_parts(&self.get);
             let (include_entities, _) = I::split_filter_parts(&self.include);
             let entities = get_entities.or(include_entities).unwrap();
@@ -154,18 +351,9 @@ where
             let (include_entities, include_sparse) = I::split_filter_parts(&self.include);
             let (_, exclude_sparse) = E::split_filter_parts(&self.exclude);
 
-            let entities = match (get_entities, include_entities) {
-                (Some(get_entities), Some(include_entities)) => {
-                    if get_entities.len() <= include_entities.len() {
-                        get_entities
-                    } else {
-                        include_entities
-                    }
-                }
-                (Some(get_entities), None) => get_entities,
-                (None, Some(include_entities)) => include_entities,
-                (None, None) => &[],
-            };
+            let (entities, matched) = self.sparse_iter_entities(get_entities, include_entities);
+            #[cfg(feature = "std")]
+            self.record(entities.len());
 
             ParIter::Sparse(SparseParIter::new(
                 entities,
@@ -173,6 +361,7 @@ where
                 include_sparse,
                 get_sparse,
                 get_data,
+                matched,
             ))
         }
     }
@@ -186,29 +375,372 @@ where
         self.par_iter().for_each(f);
     }
 
+    /// Calls `f` in parallel for all items that match the query, passing it
+    /// a value created by `init`. Values are reused across the items processed
+    /// by the same parallel task, which makes them suitable for scratch state.
+    #[cfg(feature = "parallel")]
+    pub fn par_for_each_init<T, INIT, F>(&mut self, init: INIT, f: F)
+    where
+        INIT: Fn() -> T + Send + Sync,
+        F: Fn(&mut T, G::Item<'_>) + Send + Sync,
+    {
+        self.par_iter().for_each_init(init, f);
+    }
+
     /// Returns ordered slices of all items that match the query, if the query
     // is grouped.
     #[must_use]
     pub fn slice(&mut self) -> Option<G::Slice<'_>> {
-        let range = self.get_group_range()?;
+        let range = self.get_group_range().ok()?;
+        #[cfg(feature = "std")]
+        self.record(range.len());
         let (get_entities, get_parts) = G::split_dense_parts(&self.get);
         let (include_entities, _) = I::split_filter_parts(&self.include);
         let entities = get_entities.or(include_entities).unwrap_or(&[]);
         unsafe { Some(G::slice_raw(get_parts, entities, range)) }
     }
 
+    /// Returns an iterator over all combinations of `K` distinct items that
+    /// match the query, like every unordered pair for `K = 2`.
+    ///
+    /// Grouped queries are iterated by dense index. Otherwise, the matched
+    /// entities are collected first.
+    pub fn iter_combinations<const K: usize>(&mut self) -> CombinationIter<'_, G, K>
+    where
+        G: ReadOnlyQuery,
+    {
+        let matched = self.matched();
+        let (entities, get_sparse, get_data) = self.item_parts();
+        CombinationIter::new(matched, entities, get_sparse, get_data)
+    }
+
+    /// Returns a lending iterator over all combinations of `K` distinct items
+    /// that match the query, which may give mutable access to components.
+    pub fn iter_combinations_mut<const K: usize>(
+        &mut self,
+    ) -> CombinationIterMut<'_, 'a, G, I, E, K> {
+        let matched = self.matched();
+        CombinationIterMut::new(self, matched)
+    }
+
+    /// Returns an iterator over runs of items that match the query and are
+    /// stored consecutively, as slices along with their entities.
+    ///
+    /// Grouped queries return all items in one run. Otherwise, a run lasts
+    /// while the dense indexes of the items increase together in all storages
+    /// sliced by the query.
+    pub fn iter_chunks(&mut self) -> ChunkIter<'_, G, I, E> {
+        if let Ok(range) = self.get_group_range() {
+            #[cfg(feature = "std")]
+            self.record(range.len());
+            let (get_entities, get_data) = G::split_dense_parts(&self.get);
+            let (include_entities, _) = I::split_filter_parts(&self.include);
+            let entities = get_entities.or(include_entities).unwrap_or(&[]);
+
+            let chunk = (!range.is_empty()).then(|| unsafe {
+                let chunk_entities = entities.get_unchecked(range.clone());
+                (chunk_entities, G::slice_raw(get_data, entities, range))
+            });
+
+            ChunkIter::Dense(chunk.into_iter())
+        } else {
+            let (get_entities, get_sparse, get_data) = G::split_sparse_parts(&self.get);
+            let (include_entities, include_sparse) = I::split_filter_parts(&self.include);
+            let (_, exclude_sparse) = E::split_filter_parts(&self.exclude);
+
+            let (_, entities) = self.sparse_entities(get_entities, include_entities);
+            #[cfg(feature = "std")]
+            self.record(entities.len());
+
+            ChunkIter::Sparse(SparseChunkIter::new(
+                entities,
+                exclude_sparse,
+                include_sparse,
+                get_sparse,
+                get_data,
+            ))
+        }
+    }
+
+    /// Caches the storage lookups and grouping information of the query, so
+    /// it can be borrowed again with less overhead.
     #[must_use]
-    fn get_group_range(&self) -> Option<Range<usize>> {
-        let get_info = self.get_info?;
-        let include_info = self.include_info?;
-        let exclude_info = self.exclude_info?;
+    pub fn prepare(&self) -> PreparedQuery<G, I, E> {
+        PreparedQuery {
+            world_id: self.world.components.id,
+            generation: self.world.components.generation,
+            get: G::prepare(self.world),
+            include: I::prepare(self.world),
+            exclude: E::prepare(self.world),
+            get_info: self.get_info,
+            include_info: self.include_info,
+            exclude_info: self.exclude_info,
+            bounds: self.bounds.unwrap_or_else(|| self.group_bounds()),
+            non_owning: self.non_owning,
+        }
+    }
+
+    /// Returns the strategy used to iterate the items of the query and, if
+    /// the query cannot be iterated densely, the reason why.
+    #[must_use]
+    pub fn explain(&self) -> QueryPlan {
+        match self.get_group_range() {
+            Ok(range) => QueryPlan::Dense { range },
+            Err(rejection) => {
+                if let Some(entities) = self.matching_non_owning_entities() {
+                    return QueryPlan::NonOwningGroup {
+                        len: entities.len(),
+                        rejection,
+                    };
+                }
+
+                let (get_entities, _) = G::split_filter_parts(&self.get);
+                let (include_entities, _) = I::split_filter_parts(&self.include);
+                let (driver, entities) = self.sparse_entities(get_entities, include_entities);
+
+                let driver_component = match driver {
+                    SparseDriver::Get => G::driver_component(&self.get),
+                    SparseDriver::Include => I::driver_component(&self.include),
+                    _ => None,
+                };
+
+                QueryPlan::Sparse {
+                    driver,
+                    driver_component,
+                    len: entities.len(),
+                    rejection,
+                }
+            }
+        }
+    }
+
+    /// Returns the shortest list of entities that can drive sparse iteration,
+    /// along with its source. Non-owning groups are used when all of their
+    /// component types are required by the query.
+    #[must_use]
+    fn sparse_entities<'b>(
+        &'b self,
+        get_entities: Option<&'b [Entity]>,
+        include_entities: Option<&'b [Entity]>,
+    ) -> (SparseDriver, &'b [Entity]) {
+        let (driver, entities) = match (get_entities, include_entities) {
+            (Some(get_entities), Some(include_entities)) => {
+                if get_entities.len() <= include_entities.len() {
+                    (SparseDriver::Get, get_entities)
+                } else {
+                    (SparseDriver::Include, include_entities)
+                }
+            }
+            (Some(get_entities), None) => (SparseDriver::Get, get_entities),
+            (None, Some(include_entities)) => (SparseDriver::Include, include_entities),
+            (None, None) if G::SPARSE_ONLY || I::SPARSE_ONLY || G::EXCLUDES || I::EXCLUDES => {
+                (SparseDriver::AllEntities, self.world.entities())
+            }
+            (None, None) => return (SparseDriver::None, &[]),
+        };
+
+        self.world
+            .components
+            .non_owning_entities(self.non_owning.subset_mask)
+            .filter(|group_entities| group_entities.len() < entities.len())
+            .map_or((driver, entities), |group_entities| {
+                (SparseDriver::NonOwningGroup, group_entities)
+            })
+    }
+
+    /// Returns the entities of the non-owning group whose component types are
+    /// exactly the ones required by the "get" and "include" parts of the
+    /// query, if any. All of these entities match both parts.
+    fn matching_non_owning_entities(&self) -> Option<&[Entity]> {
+        if G::SPARSE_ONLY || I::SPARSE_ONLY || G::EXCLUDES || I::EXCLUDES {
+            return None;
+        }
+
+        self.world
+            .components
+            .non_owning_group_entities(self.non_owning.exact?)
+    }
+
+    /// Finds the non-owning groups of the `world` that can drive the
+    /// iteration of the query. Runs once when the query is borrowed.
+    #[must_use]
+    fn non_owning_match(world: &World) -> NonOwningMatch {
+        if world.components.non_owning_groups.is_empty() {
+            return NonOwningMatch::default();
+        }
+
+        let mut components = Vec::new();
+        G::add_required_components(&mut components);
+        I::add_required_components(&mut components);
+        components.sort_unstable();
+        components.dedup();
+        world.components.non_owning_match(&components)
+    }
+
+    /// Returns the entities that drive sparse iteration and whether they are
+    /// known to match the "get" and "include" parts of the query.
+    fn sparse_iter_entities<'b>(
+        &'b self,
+        get_entities: Option<&'b [Entity]>,
+        include_entities: Option<&'b [Entity]>,
+    ) -> (&'b [Entity], bool) {
+        match self.matching_non_owning_entities() {
+            Some(entities) => (entities, true),
+            None => (
+                self.sparse_entities(get_entities, include_entities).1,
+                false,
+            ),
+        }
+    }
+
+    /// Returns the entities that drive the iteration of the query, in
+    /// iteration order, and whether they must be checked against the query.
+    fn driving_entities(&self) -> (&[Entity], bool) {
+        if let Ok(range) = self.get_group_range() {
+            let (get_entities, _) = G::split_dense_parts(&self.get);
+            let (include_entities, _) = I::split_filter_parts(&self.include);
+            let entities = get_entities.or(include_entities).unwrap();
+            (&entities[range], false)
+        } else {
+            let (get_entities, _) = G::split_filter_parts(&self.get);
+            let (include_entities, _) = I::split_filter_parts(&self.include);
+            let (entities, matched) = self.sparse_iter_entities(get_entities, include_entities);
+            let unfiltered =
+                !E::SPARSE_ONLY && matches!(self.exclude_info, Some(QueryGroupInfo::Empty));
+            (entities, !(matched && unfiltered))
+        }
+    }
+
+    /// Calls `f` with the entity and item of all matches of the query, in
+    /// iteration order, until it returns `false`.
+    fn visit_matched<F>(&self, mut f: F)
+    where
+        F: FnMut(Entity, G::Item<'_>) -> bool,
+    {
+        let (_, get_sparse, get_data) = G::split_sparse_parts(&self.get);
+
+        for entity in self.entities() {
+            let item =
+                unsafe { G::get_sparse_raw(get_sparse, get_data, entity).unwrap_unchecked() };
+
+            if !f(entity, item) {
+                break;
+            }
+        }
+    }
 
-        unsafe {
-            self.world
-                .components
-                .group_range(&get_info.add_query(&include_info)?, &exclude_info)
+    /// Returns the items that match the query, for combination iterators.
+    fn matched(&self) -> Matched {
+        match self.get_group_range() {
+            Ok(range) => Matched::Dense(range),
+            Err(_) => Matched::Sparse(self.entities().collect()),
         }
     }
+
+    /// Returns the dense entities, sparse vecs and data used to get items.
+    pub(crate) fn item_parts(&self) -> (&[Entity], G::Sparse<'_>, G::Data<'_>) {
+        let (get_entities, get_sparse, get_data) = G::split_sparse_parts(&self.get);
+        let (dense_entities, _) = G::split_dense_parts(&self.get);
+        let (include_entities, _) = I::split_filter_parts(&self.include);
+        let entities = dense_entities.or(include_entities).or(get_entities);
+        (entities.unwrap_or(&[]), get_sparse, get_data)
+    }
+
+    /// Returns the item of an `entity` that matches the query.
+    unsafe fn get_matched(&mut self, entity: Entity) -> G::Item<'_> {
+        let (_, get_sparse, get_data) = G::split_sparse_parts(&self.get);
+        G::get_sparse_raw(get_sparse, get_data, entity).unwrap_unchecked()
+    }
+
+    /// Returns an iterator that looks up the items of `entities`, which must
+    /// all match the query.
+    fn lookup(&mut self, entities: Vec<Entity>) -> SortedIter<'_, G, I, E> {
+        #[cfg(feature = "std")]
+        self.record(entities.len());
+        self.lookup_inner(entities)
+    }
+
+    /// Returns an iterator that looks up the items of `entities`, which must
+    /// all match the query, without recording the run.
+    fn lookup_inner(&mut self, entities: Vec<Entity>) -> SortedIter<'_, G, I, E> {
+        let (_, get_sparse, get_data) = G::split_sparse_parts(&self.get);
+        unsafe { SortedIter::Lookup(LookupIter::new(entities, get_sparse, get_data)) }
+    }
+
+    /// Records a run of the query if profiling is enabled on the world.
+    #[cfg(feature = "std")]
+    #[inline]
+    fn record(&self, visited: usize) {
+        self.world.profiler.record::<G, I, E>(visited);
+    }
+
+    fn get_group_range(&self) -> Result<Range<usize>, DenseRejection> {
+        if G::SPARSE_ONLY || I::SPARSE_ONLY || E::SPARSE_ONLY {
+            return Err(DenseRejection::SparseFilter);
+        }
+
+        let bounds = match self.bounds {
+            Some(bounds) => bounds?,
+            None => self.group_bounds()?,
+        };
+
+        let (get_entities, _) = G::split_dense_parts(&self.get);
+        let (include_entities, _) = I::split_filter_parts(&self.include);
+        let view_len = get_entities.or(include_entities).map_or(0, <[Entity]>::len);
+
+        unsafe { self.world.components.group_range(bounds, view_len) }
+    }
+
+    fn group_bounds(&self) -> Result<GroupBounds, DenseRejection> {
+        let info = self
+            .get_info
+            .zip(self.include_info)
+            .and_then(|(get_info, include_info)| get_info.add_query(&include_info));
+
+        let (Some(info), Some(exclude_info)) = (info, self.exclude_info) else {
+            return Err(DenseRejection::NoGroup);
+        };
+
+        self.world.components.group_bounds(&info, &exclude_info)
+    }
+}
+
+/// Adds the components excluded by the parts of `Q`, like those of a
+/// [`Not`](crate::query::Not) filter, to the grouping information of the
+/// components excluded by a query.
+#[must_use]
+fn add_exclusions<Q>(world: &World, exclude_info: Option<QueryGroupInfo>) -> Option<QueryGroupInfo>
+where
+    Q: Query,
+{
+    if !Q::EXCLUDES {
+        return exclude_info;
+    }
+
+    exclude_info?.add_exclusion(&Q::exclude_group_info(world)?)
+}
+
+/// Error returned by [`QueryAll::single`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SingleError {
+    /// No items match the query.
+    NoMatches,
+    /// More than one item matches the query.
+    MultipleMatches,
+}
+
+impl fmt::Display for SingleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoMatches => f.write_str("No items match the query"),
+            Self::MultipleMatches => f.write_str("More than one item matches the query"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SingleError {
+    // Empty
 }
 
 #[allow(clippy::into_iter_without_iter)]
@@ -1,6 +1,7 @@
 use crate::entity::Entity;
 use crate::query::Query;
 use crate::World;
+use core::fmt;
 
 /// Queries one item that matches the query.
 #[must_use]
@@ -102,6 +103,49 @@ where
         G::get(&mut self.get, entity)
     }
 
+    /// Returns the items mapped to all `entities`, if they all match the query
+    /// and are distinct.
+    ///
+    /// See [`try_get_many_mut`](Self::try_get_many_mut) for the reason of a
+    /// failure.
+    #[must_use]
+    pub fn get_many_mut<const N: usize>(
+        &mut self,
+        entities: [Entity; N],
+    ) -> Option<[G::Item<'_>; N]> {
+        self.try_get_many_mut(entities).ok()
+    }
+
+    /// Returns the items mapped to all `entities`, or an error if any of them
+    /// doesn't match the query or shares its index with another one.
+    pub fn try_get_many_mut<const N: usize>(
+        &mut self,
+        entities: [Entity; N],
+    ) -> Result<[G::Item<'_>; N], GetManyError> {
+        for (i, &entity) in entities.iter().enumerate() {
+            let other = entities[..i]
+                .iter()
+                .find(|other| other.sparse() == entity.sparse());
+
+            match other {
+                Some(&other) if other == entity => return Err(GetManyError::Duplicate(entity)),
+                Some(&other) => return Err(GetManyError::Aliased(other, entity)),
+                None => (),
+            }
+
+            if !self.contains(entity) {
+                return Err(GetManyError::NoMatch(entity));
+            }
+        }
+
+        let (_, sparse, data) = G::split_sparse_parts(&self.get);
+
+        // All entities match the query and have distinct indexes, so the
+        // items exist and don't alias each other.
+        Ok(entities
+            .map(|entity| unsafe { G::get_sparse_raw(sparse, data, entity).unwrap_unchecked() }))
+    }
+
     /// Calls `f` for the item mapped to `entity`, if any.
     #[must_use]
     pub fn map<T, F>(&mut self, entity: Entity, f: F) -> Option<T>
@@ -111,3 +155,29 @@ where
         self.get(entity).map(f)
     }
 }
+
+/// Error returned by [`QueryOne::try_get_many_mut`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GetManyError {
+    /// The entity was requested more than once.
+    Duplicate(Entity),
+    /// The entities share the same index, so their items would alias.
+    Aliased(Entity, Entity),
+    /// The entity doesn't match the query.
+    NoMatch(Entity),
+}
+
+impl fmt::Display for GetManyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Duplicate(entity) => write!(f, "Entity {entity:?} was requested more than once"),
+            Self::Aliased(a, b) => write!(f, "Entities {a:?} and {b:?} share the same index"),
+            Self::NoMatch(entity) => write!(f, "Entity {entity:?} doesn't match the query"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for GetManyError {
+    // Empty
+}
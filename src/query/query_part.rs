@@ -1,6 +1,7 @@
-use crate::component::{Component, View, ViewGroupInfo, ViewMut};
+use crate::component::{Component, ComponentData, QueryGroupInfo, View, ViewGroupInfo, ViewMut};
 use crate::entity::{Entity, SparseVec};
 use crate::World;
+use alloc::vec::Vec;
 use core::ops::Range;
 use core::ptr::NonNull;
 use core::slice;
@@ -30,13 +31,62 @@ pub unsafe trait QueryPart {
     /// Data used for sparse and dense iteration.
     type Data<'a>: Copy;
 
+    /// Storage lookups cached by [`PreparedQuery`](crate::query::PreparedQuery).
+    type State: Copy;
+
+    /// Whether the view restricts the matched entities but can only be
+    /// evaluated per entity, which prevents the query from being iterated
+    /// densely.
+    const SPARSE_ONLY: bool = false;
+
+    /// Whether the view only matches entities that lack some components, like
+    /// [`Not`](crate::query::Not), which requires the view to be checked for
+    /// every entity it doesn't drive.
+    const EXCLUDES: bool = false;
+
     /// Borrows a view from the `world`.
     #[must_use]
     fn borrow(world: &World) -> Self::View<'_>;
 
+    /// Looks up the storages borrowed by the view.
+    #[must_use]
+    fn prepare(world: &World) -> Self::State;
+
+    /// Borrows a view from the `world` using the storages looked up by
+    /// [`prepare`](Self::prepare).
+    ///
+    /// # Safety
+    ///
+    /// `state` must have been returned by `prepare` on the same `world`, while
+    /// it had the same layout generation.
+    #[must_use]
+    unsafe fn borrow_prepared(world: &World, state: Self::State) -> Self::View<'_>;
+
     /// Borrows a view from the `world` along with grouping information.
+    ///
+    /// Views that don't restrict the matched entities, like optional views,
+    /// return [`QueryGroupInfo::Empty`] and are looked up per item, so they
+    /// don't prevent the other views of a query from being iterated densely.
+    /// Views that can't be grouped return `None`.
+    #[must_use]
+    fn borrow_with_group_info(world: &World) -> (Self::View<'_>, Option<QueryGroupInfo>);
+
+    /// Returns the grouping information of the components the view requires
+    /// matched entities to lack, or `None` if they can't be grouped.
+    #[must_use]
+    fn exclude_group_info(_world: &World) -> Option<QueryGroupInfo> {
+        Some(QueryGroupInfo::Empty)
+    }
+
+    /// Adds the component types required by the view to `components`.
+    fn add_required_components(components: &mut Vec<ComponentData>);
+
+    /// Returns the component type of the storage whose entities are returned
+    /// by [`split_filter_parts`](Self::split_filter_parts), if any.
     #[must_use]
-    fn borrow_with_group_info(world: &World) -> (Self::View<'_>, Option<ViewGroupInfo>);
+    fn driver_component(_view: &Self::View<'_>) -> Option<ComponentData> {
+        None
+    }
 
     /// Returns whether `entity` is present in the view.
     #[must_use]
@@ -69,6 +119,12 @@ pub unsafe trait QueryPart {
     #[must_use]
     fn contains_raw(sparse: Self::Sparse<'_>, sparse_index: usize) -> bool;
 
+    /// Returns the dense index of `entity` in the storage sliced by
+    /// [`slice_raw`](Self::slice_raw), or `None` if the view doesn't slice a
+    /// storage.
+    #[must_use]
+    fn get_dense_index_raw(sparse: Self::Sparse<'_>, entity: Entity) -> Option<usize>;
+
     /// Returns the sparse key extracted from the sparse vecs.
     #[must_use]
     fn get_sparse_key_raw(sparse: Self::Sparse<'_>, entity: Entity) -> Option<Self::SparseKey>;
@@ -90,6 +146,34 @@ pub unsafe trait QueryPart {
     ) -> Self::Slice<'a>;
 }
 
+/// Marker for query parts that never give mutable access to components, so
+/// their items can be held for multiple entities at once.
+///
+/// # Safety
+///
+/// Items returned by the query part must be able to alias each other.
+pub unsafe trait ReadOnlyQueryPart: QueryPart {
+    // Empty
+}
+
+unsafe impl ReadOnlyQueryPart for Entity {
+    // Empty
+}
+
+unsafe impl<T> ReadOnlyQueryPart for &'_ T
+where
+    T: Component,
+{
+    // Empty
+}
+
+unsafe impl<T> ReadOnlyQueryPart for Option<&'_ T>
+where
+    T: Component,
+{
+    // Empty
+}
+
 unsafe impl QueryPart for Entity {
     type View<'a> = ();
     type Item<'a> = Entity;
@@ -97,6 +181,7 @@ unsafe impl QueryPart for Entity {
     type Sparse<'a> = ();
     type SparseKey = Entity;
     type Data<'a> = ();
+    type State = ();
 
     #[inline]
     fn borrow(_world: &World) -> Self::View<'_> {
@@ -104,8 +189,23 @@ unsafe impl QueryPart for Entity {
     }
 
     #[inline]
-    fn borrow_with_group_info(_world: &World) -> (Self::View<'_>, Option<ViewGroupInfo>) {
-        ((), None)
+    fn prepare(_world: &World) -> Self::State {
+        // Empty
+    }
+
+    #[inline]
+    unsafe fn borrow_prepared(_world: &World, _state: Self::State) -> Self::View<'_> {
+        // Empty
+    }
+
+    #[inline]
+    fn borrow_with_group_info(_world: &World) -> (Self::View<'_>, Option<QueryGroupInfo>) {
+        ((), Some(QueryGroupInfo::Empty))
+    }
+
+    #[inline]
+    fn add_required_components(_components: &mut Vec<ComponentData>) {
+        // Empty
     }
 
     #[inline]
@@ -150,6 +250,11 @@ unsafe impl QueryPart for Entity {
         true
     }
 
+    #[inline]
+    fn get_dense_index_raw(_sparse: Self::Sparse<'_>, _entity: Entity) -> Option<usize> {
+        None
+    }
+
     #[inline]
     fn get_sparse_key_raw<'a>(
         _sparse: Self::Sparse<'_>,
@@ -192,12 +297,21 @@ where
     type Sparse<'a> = &'a SparseVec;
     type SparseKey = usize;
     type Data<'a> = NonNull<T>;
+    type State = usize;
 
     fn borrow(world: &World) -> Self::View<'_> {
         world.borrow::<T>()
     }
 
-    fn borrow_with_group_info(world: &World) -> (Self::View<'_>, Option<ViewGroupInfo>) {
+    fn prepare(world: &World) -> Self::State {
+        world.components.storage_index::<T>()
+    }
+
+    unsafe fn borrow_prepared(world: &World, state: Self::State) -> Self::View<'_> {
+        world.components.borrow_at::<T>(state)
+    }
+
+    fn borrow_with_group_info(world: &World) -> (Self::View<'_>, Option<QueryGroupInfo>) {
         let (view, info) = world.borrow_with_group_info::<T>();
 
         let info = ViewGroupInfo {
@@ -205,7 +319,15 @@ where
             len: view.len(),
         };
 
-        (view, Some(info))
+        (view, Some(QueryGroupInfo::One(info)))
+    }
+
+    fn add_required_components(components: &mut Vec<ComponentData>) {
+        components.push(ComponentData::new::<T>());
+    }
+
+    fn driver_component(_view: &Self::View<'_>) -> Option<ComponentData> {
+        Some(ComponentData::new::<T>())
     }
 
     fn contains(view: &Self::View<'_>, entity: Entity) -> bool {
@@ -240,6 +362,10 @@ where
         sparse.contains_sparse(sparse_index)
     }
 
+    fn get_dense_index_raw(sparse: Self::Sparse<'_>, entity: Entity) -> Option<usize> {
+        Some(sparse.get_sparse(entity.sparse())? as usize)
+    }
+
     fn get_sparse_key_raw<'a>(sparse: Self::Sparse<'_>, entity: Entity) -> Option<Self::SparseKey> {
         Some(sparse.get_sparse(entity.sparse())? as usize)
     }
@@ -257,7 +383,7 @@ where
         _entities: &'a [Entity],
         range: Range<usize>,
     ) -> Self::Slice<'a> {
-        slice::from_raw_parts(data.as_ptr(), range.end - range.start)
+        slice::from_raw_parts(data.add(range.start).as_ptr(), range.end - range.start)
     }
 }
 
@@ -271,12 +397,21 @@ where
     type Sparse<'a> = &'a SparseVec;
     type SparseKey = usize;
     type Data<'a> = NonNull<T>;
+    type State = usize;
 
     fn borrow(world: &World) -> Self::View<'_> {
         world.borrow_mut::<T>()
     }
 
-    fn borrow_with_group_info(world: &World) -> (Self::View<'_>, Option<ViewGroupInfo>) {
+    fn prepare(world: &World) -> Self::State {
+        world.components.storage_index::<T>()
+    }
+
+    unsafe fn borrow_prepared(world: &World, state: Self::State) -> Self::View<'_> {
+        world.components.borrow_mut_at::<T>(state)
+    }
+
+    fn borrow_with_group_info(world: &World) -> (Self::View<'_>, Option<QueryGroupInfo>) {
         let (view, info) = world.borrow_with_group_info_mut::<T>();
 
         let info = ViewGroupInfo {
@@ -284,7 +419,15 @@ where
             len: view.len(),
         };
 
-        (view, Some(info))
+        (view, Some(QueryGroupInfo::One(info)))
+    }
+
+    fn add_required_components(components: &mut Vec<ComponentData>) {
+        components.push(ComponentData::new::<T>());
+    }
+
+    fn driver_component(_view: &Self::View<'_>) -> Option<ComponentData> {
+        Some(ComponentData::new::<T>())
     }
 
     fn contains(view: &Self::View<'_>, entity: Entity) -> bool {
@@ -319,6 +462,10 @@ where
         sparse.contains_sparse(sparse_index)
     }
 
+    fn get_dense_index_raw(sparse: Self::Sparse<'_>, entity: Entity) -> Option<usize> {
+        Some(sparse.get_sparse(entity.sparse())? as usize)
+    }
+
     fn get_sparse_key_raw(sparse: Self::Sparse<'_>, entity: Entity) -> Option<Self::SparseKey> {
         Some(sparse.get_sparse(entity.sparse())? as usize)
     }
@@ -336,7 +483,7 @@ where
         _entities: &'a [Entity],
         range: Range<usize>,
     ) -> Self::Slice<'a> {
-        slice::from_raw_parts_mut(data.as_ptr(), range.end - range.start)
+        slice::from_raw_parts_mut(data.add(range.start).as_ptr(), range.end - range.start)
     }
 }
 
@@ -350,13 +497,26 @@ where
     type Sparse<'a> = ();
     type SparseKey = Entity;
     type Data<'a> = (&'a SparseVec, NonNull<T>);
+    type State = usize;
 
     fn borrow(world: &World) -> Self::View<'_> {
         world.borrow::<T>()
     }
 
-    fn borrow_with_group_info(world: &World) -> (Self::View<'_>, Option<ViewGroupInfo>) {
-        (world.borrow::<T>(), None)
+    fn prepare(world: &World) -> Self::State {
+        world.components.storage_index::<T>()
+    }
+
+    unsafe fn borrow_prepared(world: &World, state: Self::State) -> Self::View<'_> {
+        world.components.borrow_at::<T>(state)
+    }
+
+    fn borrow_with_group_info(world: &World) -> (Self::View<'_>, Option<QueryGroupInfo>) {
+        (world.borrow::<T>(), Some(QueryGroupInfo::Empty))
+    }
+
+    fn add_required_components(_components: &mut Vec<ComponentData>) {
+        // Empty
     }
 
     fn contains(_view: &Self::View<'_>, _entity: Entity) -> bool {
@@ -391,6 +551,10 @@ where
         true
     }
 
+    fn get_dense_index_raw(_sparse: Self::Sparse<'_>, _entity: Entity) -> Option<usize> {
+        None
+    }
+
     fn get_sparse_key_raw(_sparse: Self::Sparse<'_>, entity: Entity) -> Option<Self::SparseKey> {
         Some(entity)
     }
@@ -433,13 +597,26 @@ where
     type Sparse<'a> = ();
     type SparseKey = Entity;
     type Data<'a> = (&'a SparseVec, NonNull<T>);
+    type State = usize;
 
     fn borrow(world: &World) -> Self::View<'_> {
         world.borrow_mut::<T>()
     }
 
-    fn borrow_with_group_info(world: &World) -> (Self::View<'_>, Option<ViewGroupInfo>) {
-        (world.borrow_mut::<T>(), None)
+    fn prepare(world: &World) -> Self::State {
+        world.components.storage_index::<T>()
+    }
+
+    unsafe fn borrow_prepared(world: &World, state: Self::State) -> Self::View<'_> {
+        world.components.borrow_mut_at::<T>(state)
+    }
+
+    fn borrow_with_group_info(world: &World) -> (Self::View<'_>, Option<QueryGroupInfo>) {
+        (world.borrow_mut::<T>(), Some(QueryGroupInfo::Empty))
+    }
+
+    fn add_required_components(_components: &mut Vec<ComponentData>) {
+        // Empty
     }
 
     fn contains(_view: &Self::View<'_>, _entity: Entity) -> bool {
@@ -474,6 +651,10 @@ where
         true
     }
 
+    fn get_dense_index_raw(_sparse: Self::Sparse<'_>, _entity: Entity) -> Option<usize> {
+        None
+    }
+
     fn get_sparse_key_raw<'a>(
         _sparse: Self::Sparse<'_>,
         entity: Entity,
@@ -0,0 +1,98 @@
+use crate::component::ComponentData;
+use core::ops::Range;
+
+/// Strategy used by a [`QueryAll`](crate::query::QueryAll) to iterate its
+/// items, as returned by [`QueryAll::explain`](crate::query::QueryAll::explain).
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum QueryPlan {
+    /// The items are iterated densely, over a range of grouped components.
+    Dense {
+        /// The range of dense indexes that holds the items.
+        range: Range<usize>,
+    },
+    /// The items are iterated over the entity list of a non-owning group whose
+    /// component types are exactly the ones required by the query, so only
+    /// the excluded components are checked.
+    NonOwningGroup {
+        /// The number of entities in the group.
+        len: usize,
+        /// Why the query could not be iterated densely.
+        rejection: DenseRejection,
+    },
+    /// The items are found by iterating a list of entities and looking up
+    /// their components.
+    Sparse {
+        /// Where the list of iterated entities comes from.
+        driver: SparseDriver,
+        /// The component type of the storage that holds the list of iterated
+        /// entities, if it comes from the "get" or "include" part.
+        driver_component: Option<ComponentData>,
+        /// The number of entities in the list.
+        len: usize,
+        /// Why the query could not be iterated densely.
+        rejection: DenseRejection,
+    },
+}
+
+impl QueryPlan {
+    /// Returns whether the query is iterated densely.
+    #[inline]
+    #[must_use]
+    pub const fn is_dense(&self) -> bool {
+        matches!(self, Self::Dense { .. })
+    }
+
+    /// Returns whether the query is iterated over a matching non-owning group.
+    #[inline]
+    #[must_use]
+    pub const fn is_non_owning_group(&self) -> bool {
+        matches!(self, Self::NonOwningGroup { .. })
+    }
+
+    /// Returns whether the query is iterated sparsely.
+    #[inline]
+    #[must_use]
+    pub const fn is_sparse(&self) -> bool {
+        matches!(self, Self::Sparse { .. })
+    }
+}
+
+/// Source of the entities iterated by a sparse query.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum SparseDriver {
+    /// The shortest storage of the views in the "get" part of the query.
+    Get,
+    /// The shortest storage of the views in the "include" filter.
+    Include,
+    /// The entity list of a non-owning group whose components are all
+    /// required by the query.
+    NonOwningGroup,
+    /// All entities in the world, because the query is only restricted by
+    /// filters like [`Or`](crate::query::Or).
+    AllEntities,
+    /// The query has no views that restrict the matched entities, so no
+    /// entities are iterated.
+    None,
+}
+
+/// Reason why a query could not be iterated densely.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum DenseRejection {
+    /// The query has no views that restrict the matched entities.
+    Unconstrained,
+    /// The views of the query are not all part of the same group family, for
+    /// example because one of the components is not grouped.
+    NoGroup,
+    /// The components of the "get" and "include" views don't form exactly
+    /// one group of their family.
+    MismatchedGroup,
+    /// The excluded components are not exactly the ones a group adds over its
+    /// parent group.
+    ExcludeNotAtGroupBoundary,
+    /// The query contains a filter, like [`Or`](crate::query::Or), that can
+    /// only be evaluated per entity.
+    SparseFilter,
+    /// Grouping is deferred by [`World::batch`](crate::World::batch), so group
+    /// ranges are incomplete.
+    GroupingDeferred,
+}
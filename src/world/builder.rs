@@ -1,6 +1,8 @@
+use crate::allocator::{Allocator, SharedAllocator};
 use crate::component::{Component, ComponentData, GroupDescriptor, GroupLayout};
 use crate::world::World;
 use alloc::vec::Vec;
+use core::cmp::Ordering;
 
 /// Builder struct for creating a [`World`].
 #[must_use]
@@ -8,6 +10,8 @@ use alloc::vec::Vec;
 pub struct WorldBuilder {
     layout: GroupLayout,
     components: Vec<ComponentData>,
+    allocator: SharedAllocator,
+    track_allocations: bool,
 }
 
 impl WorldBuilder {
@@ -18,6 +22,25 @@ impl WorldBuilder {
         self
     }
 
+    /// Sets the allocator from which the world's component and entity storages
+    /// draw their memory.
+    pub fn set_allocator<A>(&mut self, allocator: A) -> &mut Self
+    where
+        A: Allocator + 'static,
+    {
+        self.allocator = SharedAllocator::new(allocator);
+        self
+    }
+
+    /// Sets whether the world tracks the number of bytes allocated by its
+    /// storages, as reported by [`World::allocated_bytes`]. Disabled by
+    /// default.
+    #[inline]
+    pub fn set_allocation_tracking(&mut self, enabled: bool) -> &mut Self {
+        self.track_allocations = enabled;
+        self
+    }
+
     /// Adds a new component group to the world.
     pub fn add_group<G>(&mut self) -> &mut Self
     where
@@ -33,6 +56,32 @@ impl WorldBuilder {
         self
     }
 
+    /// Adds a new group to the world, kept sorted by its components of type
+    /// `T`.
+    pub fn add_sorted_group<G, T>(&mut self, compare: fn(&T, &T) -> Ordering) -> &mut Self
+    where
+        G: GroupDescriptor,
+        T: Component,
+    {
+        self.layout.add_sorted_group::<G, T>(compare);
+        self
+    }
+
+    /// Adds a new non-owning group to the world.
+    pub fn add_non_owning_group<G>(&mut self) -> &mut Self
+    where
+        G: GroupDescriptor,
+    {
+        self.add_non_owning_group_dyn(G::COMPONENTS)
+    }
+
+    /// Adds a new non-owning group to the world.
+    #[inline]
+    pub fn add_non_owning_group_dyn(&mut self, components: &[ComponentData]) -> &mut Self {
+        self.layout.add_non_owning_group_dyn(components);
+        self
+    }
+
     /// Registers a new component type on the world.
     pub fn register<T>(&mut self) -> &mut Self
     where
@@ -53,7 +102,13 @@ impl WorldBuilder {
     /// Returns the newly created world.
     #[must_use]
     pub fn build(&self) -> World {
-        let mut world = World::new(&self.layout);
+        let allocator = if self.track_allocations {
+            self.allocator.tracked()
+        } else {
+            self.allocator.clone()
+        };
+
+        let mut world = World::new_in(&self.layout, allocator);
 
         for &component in &self.components {
             world.register_dyn(component);
@@ -0,0 +1,507 @@
+use crate::component::{
+    Component, ComponentData, ComponentSet, EntityComponents, GroupDescriptor, GroupEvent,
+    GroupLayout, LocalComponentStorage, View, ViewMut,
+};
+use crate::entity::Entity;
+use crate::query::Query;
+use crate::world::World;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use core::ops::Deref;
+
+/// [`World`] that can also hold local components, which are not required to be
+/// `Send` or `Sync`.
+///
+/// A local world is neither `Send` nor `Sync`, so its local components never
+/// leave the thread that created them. It dereferences to its inner world for
+/// shared access and forwards the methods that mutate the inner world, so
+/// removing an entity always drops its local components too.
+///
+/// Local components cannot be grouped and are accessible through
+/// [`borrow_local`](Self::borrow_local),
+/// [`borrow_local_mut`](Self::borrow_local_mut) and the local queries
+/// [`for_each_local`](Self::for_each_local) and
+/// [`for_each_local_mut`](Self::for_each_local_mut), which run on the current
+/// thread.
+///
+/// ```compile_fail
+/// fn assert_send<T: Send>() {}
+/// assert_send::<sparsey::world::LocalWorld>();
+/// ```
+///
+/// The inner world can't be borrowed mutably, so its entities can't be removed
+/// without their local components:
+///
+/// ```compile_fail
+/// let mut world = sparsey::world::LocalWorld::default();
+/// let _ = std::mem::take(&mut *world);
+/// ```
+#[derive(Default, Debug)]
+pub struct LocalWorld {
+    world: World,
+    local: LocalComponentStorage,
+    _phantom: PhantomData<*const ()>,
+}
+
+impl LocalWorld {
+    /// Creates a local world from the given `world`.
+    #[inline]
+    #[must_use]
+    pub fn new(world: World) -> Self {
+        Self {
+            world,
+            local: LocalComponentStorage::default(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Drops all local components and returns the inner world.
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> World {
+        self.world
+    }
+
+    /// Registers a new local component type on this world.
+    ///
+    /// Returns whether the component was newly registered.
+    pub fn register_local<T>(&mut self) -> bool
+    where
+        T: 'static,
+    {
+        let allocator = self.world.components.allocator.clone();
+        self.local.register::<T>(allocator)
+    }
+
+    /// Returns whether the local component type is registered.
+    #[must_use]
+    pub fn is_local_registered<T>(&self) -> bool
+    where
+        T: 'static,
+    {
+        self.local.is_registered::<T>()
+    }
+
+    /// Inserts a local `component` to an existing `entity`, overwriting
+    /// previous data if necessary.
+    ///
+    /// Returns whether the `component` was added successfully, i.e. whether
+    /// the `entity` existed in the world before this call.
+    pub fn insert_local<T>(&mut self, entity: Entity, component: T) -> bool
+    where
+        T: 'static,
+    {
+        if !self.world.entities.contains(entity) {
+            return false;
+        }
+
+        let _ = self.local.insert(entity, component);
+        true
+    }
+
+    /// Removes a local component from the `entity`, returning it if it
+    /// existed.
+    #[must_use = "Use `drop` to discard the component."]
+    pub fn remove_local<T>(&mut self, entity: Entity) -> Option<T>
+    where
+        T: 'static,
+    {
+        self.local.remove(entity)
+    }
+
+    /// Removes the `entity` and its associated components, including local
+    /// ones, from the world.
+    ///
+    /// Returns whether the operation was successfull, i.e. whether the entity
+    /// existed in the world before this call.
+    pub fn destroy(&mut self, entity: Entity) -> bool {
+        if !self.world.destroy(entity) {
+            return false;
+        }
+
+        self.local.strip(entity);
+        true
+    }
+
+    /// Removes the `entity` from the world, returning its components as a
+    /// type-erased collection. Local components are dropped.
+    ///
+    /// Returns `None` if the entity did not exist in the world.
+    #[must_use = "Use `destroy` to discard the components."]
+    pub fn take_entity(&mut self, entity: Entity) -> Option<EntityComponents> {
+        let components = self.world.take_entity(entity)?;
+        self.local.strip(entity);
+        Some(components)
+    }
+
+    /// Removes all entities and components, including local ones, from the
+    /// world.
+    pub fn clear(&mut self) {
+        self.world.clear();
+        self.local.clear();
+    }
+
+    /// Removes all entities and components, including local ones, from the
+    /// world and resets the entity allocator, allowing the world to reuse
+    /// previously allocated entities.
+    pub fn reset(&mut self) {
+        self.world.reset();
+        self.local.clear();
+    }
+
+    /// Calls `f` with each local component of type `T` whose entity also
+    /// matches the query `G`, along with its item. The local components drive
+    /// the iteration.
+    pub fn for_each_local<T, G>(&self, mut f: impl FnMut(&T, G::Item<'_>))
+    where
+        T: 'static,
+        G: Query,
+    {
+        let local = self.local.borrow::<T>();
+        let mut query = self.world.query_one::<G>();
+
+        for (&entity, component) in local.entities().iter().zip(local.as_slice()) {
+            if let Some(item) = query.get(entity) {
+                f(component, item);
+            }
+        }
+    }
+
+    /// Calls `f` with each local component of type `T` whose entity also
+    /// matches the query `G`, mutably, along with its item. The local
+    /// components drive the iteration.
+    pub fn for_each_local_mut<T, G>(&self, mut f: impl FnMut(&mut T, G::Item<'_>))
+    where
+        T: 'static,
+        G: Query,
+    {
+        let mut local = self.local.borrow_mut::<T>();
+        let mut query = self.world.query_one::<G>();
+
+        for i in 0..local.len() {
+            let entity = local.entities()[i];
+
+            if let Some(item) = query.get(entity) {
+                f(&mut local.as_mut_slice()[i], item);
+            }
+        }
+    }
+
+    /// Returns a shared view over all local components of type `T`.
+    #[must_use]
+    pub fn borrow_local<T>(&self) -> View<'_, T>
+    where
+        T: 'static,
+    {
+        self.local.borrow::<T>()
+    }
+
+    /// Returns an exclusive view over all local components of type `T`.
+    #[must_use]
+    pub fn borrow_local_mut<T>(&self) -> ViewMut<'_, T>
+    where
+        T: 'static,
+    {
+        self.local.borrow_mut::<T>()
+    }
+}
+
+impl LocalWorld {
+    /// Sets a new group `layout` on this world. See [`World::set_layout`].
+    #[inline]
+    pub fn set_layout(&mut self, layout: &GroupLayout) {
+        self.world.set_layout(layout);
+    }
+
+    /// Adds a new group to the layout of this world. See
+    /// [`World::add_group`].
+    pub fn add_group<G>(&mut self)
+    where
+        G: GroupDescriptor,
+    {
+        self.world.add_group::<G>();
+    }
+
+    /// Adds a new group to the layout of this world. See
+    /// [`World::add_group_dyn`].
+    #[inline]
+    pub fn add_group_dyn(&mut self, components: &[ComponentData]) {
+        self.world.add_group_dyn(components);
+    }
+
+    /// Removes a group from the layout of this world. See
+    /// [`World::remove_group`].
+    pub fn remove_group<G>(&mut self) -> bool
+    where
+        G: GroupDescriptor,
+    {
+        self.world.remove_group::<G>()
+    }
+
+    /// Removes a group from the layout of this world. See
+    /// [`World::remove_group_dyn`].
+    #[inline]
+    pub fn remove_group_dyn(&mut self, components: &[ComponentData]) -> bool {
+        self.world.remove_group_dyn(components)
+    }
+
+    /// Sorts the grouped components of `G`. See [`World::sort_group`].
+    pub fn sort_group<G>(&mut self)
+    where
+        G: GroupDescriptor,
+    {
+        self.world.sort_group::<G>();
+    }
+
+    /// Sorts the grouped components of the given group. See
+    /// [`World::sort_group_dyn`].
+    #[inline]
+    pub fn sort_group_dyn(&mut self, components: &[ComponentData]) {
+        self.world.sort_group_dyn(components);
+    }
+
+    /// Starts recording events for the group `G`. See
+    /// [`World::track_group`].
+    pub fn track_group<G>(&mut self) -> bool
+    where
+        G: GroupDescriptor,
+    {
+        self.world.track_group::<G>()
+    }
+
+    /// Starts recording events for the given group. See
+    /// [`World::track_group_dyn`].
+    #[inline]
+    pub fn track_group_dyn(&mut self, components: &[ComponentData]) -> bool {
+        self.world.track_group_dyn(components)
+    }
+
+    /// Stops recording events for the group `G`. See
+    /// [`World::untrack_group`].
+    pub fn untrack_group<G>(&mut self) -> bool
+    where
+        G: GroupDescriptor,
+    {
+        self.world.untrack_group::<G>()
+    }
+
+    /// Stops recording events for the given group. See
+    /// [`World::untrack_group_dyn`].
+    #[inline]
+    pub fn untrack_group_dyn(&mut self, components: &[ComponentData]) -> bool {
+        self.world.untrack_group_dyn(components)
+    }
+
+    /// Removes and returns the events recorded for the group `G`. See
+    /// [`World::drain_group_events`].
+    pub fn drain_group_events<G>(&mut self) -> impl Iterator<Item = GroupEvent> + '_
+    where
+        G: GroupDescriptor,
+    {
+        self.world.drain_group_events::<G>()
+    }
+
+    /// Removes and returns the events recorded for the given group. See
+    /// [`World::drain_group_events_dyn`].
+    #[inline]
+    pub fn drain_group_events_dyn(
+        &mut self,
+        components: &[ComponentData],
+    ) -> impl Iterator<Item = GroupEvent> + '_ {
+        self.world.drain_group_events_dyn(components)
+    }
+
+    /// Adds a new non-owning group to the layout of this world. See
+    /// [`World::add_non_owning_group`].
+    pub fn add_non_owning_group<G>(&mut self)
+    where
+        G: GroupDescriptor,
+    {
+        self.world.add_non_owning_group::<G>();
+    }
+
+    /// Adds a new non-owning group to the layout of this world. See
+    /// [`World::add_non_owning_group_dyn`].
+    #[inline]
+    pub fn add_non_owning_group_dyn(&mut self, components: &[ComponentData]) {
+        self.world.add_non_owning_group_dyn(components);
+    }
+
+    /// Removes a non-owning group from the layout of this world. See
+    /// [`World::remove_non_owning_group`].
+    pub fn remove_non_owning_group<G>(&mut self) -> bool
+    where
+        G: GroupDescriptor,
+    {
+        self.world.remove_non_owning_group::<G>()
+    }
+
+    /// Removes a non-owning group from the layout of this world. See
+    /// [`World::remove_non_owning_group_dyn`].
+    #[inline]
+    pub fn remove_non_owning_group_dyn(&mut self, components: &[ComponentData]) -> bool {
+        self.world.remove_non_owning_group_dyn(components)
+    }
+
+    /// Enables or disables query profiling. See
+    /// [`World::set_query_profiling`].
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn set_query_profiling(&mut self, enabled: bool) {
+        self.world.set_query_profiling(enabled);
+    }
+
+    /// Discards all recorded query stats. See [`World::clear_query_stats`].
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn clear_query_stats(&mut self) {
+        self.world.clear_query_stats();
+    }
+
+    /// Registers a new component type on this world. See [`World::register`].
+    pub fn register<T>(&mut self) -> bool
+    where
+        T: Component,
+    {
+        self.world.register::<T>()
+    }
+
+    /// Registers a new component type on this world. See
+    /// [`World::register_dyn`].
+    #[inline]
+    pub fn register_dyn(&mut self, component: ComponentData) -> bool {
+        self.world.register_dyn(component)
+    }
+
+    /// Creates a new entity with the given `components`. See
+    /// [`World::create`].
+    pub fn create<C>(&mut self, components: C) -> Entity
+    where
+        C: ComponentSet,
+    {
+        self.world.create(components)
+    }
+
+    /// Creates new entities with the `components` produced by the iterator.
+    /// See [`World::extend`].
+    pub fn extend<C, I>(&mut self, components: I) -> &[Entity]
+    where
+        C: ComponentSet,
+        I: IntoIterator<Item = C>,
+    {
+        self.world.extend(components)
+    }
+
+    /// Inserts the components produced by the iterator to their paired
+    /// entities. See [`World::insert_batch`].
+    pub fn insert_batch<C, I>(&mut self, components: I)
+    where
+        C: ComponentSet,
+        I: IntoIterator<Item = (Entity, C)>,
+    {
+        self.world.insert_batch(components);
+    }
+
+    /// Runs `f` with grouping deferred. See [`World::batch`].
+    ///
+    /// Unlike [`World::batch`], `f` receives the local world, so entities
+    /// removed in the batch also lose their local components.
+    pub fn batch<R>(&mut self, f: impl FnOnce(&mut LocalWorld) -> R) -> R {
+        if !self.world.components.defer_grouping() {
+            return f(self);
+        }
+
+        let guard = LocalBatchGuard(self);
+        f(guard.0)
+    }
+
+    /// Creates a new entity with the given type-erased `components`. See
+    /// [`World::spawn_components`].
+    pub fn spawn_components(&mut self, components: EntityComponents) -> Entity {
+        self.world.spawn_components(components)
+    }
+
+    /// Adds the entities created with [`World::create_atomic`] to the world.
+    #[inline]
+    pub fn maintain(&mut self) {
+        self.world.maintain();
+    }
+
+    /// Inserts `components` to an existing `entity`. See [`World::insert`].
+    pub fn insert<C>(&mut self, entity: Entity, components: C) -> bool
+    where
+        C: ComponentSet,
+    {
+        self.world.insert(entity, components)
+    }
+
+    /// Inserts `components` to an existing `entity`, returning the overwritten
+    /// components. See [`World::replace`].
+    #[must_use = "Use `insert` to discard the overwritten components."]
+    pub fn replace<C>(&mut self, entity: Entity, components: C) -> Option<C::Remove>
+    where
+        C: ComponentSet,
+    {
+        self.world.replace(entity, components)
+    }
+
+    /// Removes components from the `entity`, returning the removed components.
+    /// See [`World::remove`].
+    #[must_use = "Use `delete` to discard the components."]
+    pub fn remove<C>(&mut self, entity: Entity) -> C::Remove
+    where
+        C: ComponentSet,
+    {
+        self.world.remove::<C>(entity)
+    }
+
+    /// Removes components from the `entity`, without returning them. See
+    /// [`World::delete`].
+    pub fn delete<C>(&mut self, entity: Entity)
+    where
+        C: ComponentSet,
+    {
+        self.world.delete::<C>(entity);
+    }
+
+    /// Removes all components of type `T` from the world. See
+    /// [`World::clear_component`].
+    pub fn clear_component<T>(&mut self)
+    where
+        T: Component,
+    {
+        self.world.clear_component::<T>();
+    }
+
+    /// Removes all components of type `T` from the world, returning them. See
+    /// [`World::take_component`].
+    #[must_use = "Use `clear_component` to discard the components."]
+    pub fn take_component<T>(&mut self) -> Vec<(Entity, T)>
+    where
+        T: Component,
+    {
+        self.world.take_component::<T>()
+    }
+}
+
+impl From<World> for LocalWorld {
+    fn from(world: World) -> Self {
+        Self::new(world)
+    }
+}
+
+impl Deref for LocalWorld {
+    type Target = World;
+
+    fn deref(&self) -> &Self::Target {
+        &self.world
+    }
+}
+
+struct LocalBatchGuard<'a>(&'a mut LocalWorld);
+
+impl Drop for LocalBatchGuard<'_> {
+    fn drop(&mut self) {
+        self.0.world.components.finish_deferred_grouping();
+    }
+}
@@ -1,21 +1,37 @@
 //! Manage and query entities and their associated components.
 
 mod builder;
+mod local_world;
+
+#[cfg(feature = "std")]
+mod query_stats;
 
 pub use self::builder::*;
+pub use self::local_world::*;
+
+#[cfg(feature = "std")]
+pub use self::query_stats::QueryStats;
+
+#[cfg(feature = "std")]
+pub(crate) use self::query_stats::QueryProfiler;
 
+use crate::allocator::SharedAllocator;
 use crate::component::{
-    Component, ComponentData, ComponentSet, ComponentStorage, GroupInfo, GroupLayout, View, ViewMut,
+    Component, ComponentData, ComponentSet, ComponentStorage, EntityComponents, GroupDescriptor,
+    GroupEvent, GroupInfo, GroupLayout, GroupSummary, View, ViewMut,
 };
 use crate::entity::{Entity, EntityStorage};
 use crate::query::{Query, QueryAll, QueryOne};
+use alloc::vec::Vec;
 use core::any::TypeId;
 
 /// Collection for entities and their associated components.
-#[derive(Default, Debug)]
+#[derive(Debug)]
 pub struct World {
     pub(crate) entities: EntityStorage,
     pub(crate) components: ComponentStorage,
+    #[cfg(feature = "std")]
+    pub(crate) profiler: QueryProfiler,
 }
 
 impl World {
@@ -29,21 +45,278 @@ impl World {
     #[inline]
     #[must_use]
     pub fn new(layout: &GroupLayout) -> Self {
+        Self::new_in(layout, SharedAllocator::Global)
+    }
+
+    #[must_use]
+    pub(crate) fn new_in(layout: &GroupLayout, allocator: SharedAllocator) -> Self {
         Self {
-            entities: EntityStorage::default(),
-            components: ComponentStorage::new(layout),
+            entities: EntityStorage::new_in(allocator.clone()),
+            components: ComponentStorage::new(layout, allocator),
+            #[cfg(feature = "std")]
+            profiler: QueryProfiler::default(),
         }
     }
 
     /// Sets a new group `layout` on this world.
     ///
-    /// This operation requires iteration over all entities in the world, so it
-    /// is best called when the world is empty.
+    /// Groups that are also present in the current layout keep their grouped
+    /// entities and tracked events. New groups are grouped from the storages
+    /// of their family, skipping the entities of larger groups that were kept.
     #[inline]
     pub fn set_layout(&mut self, layout: &GroupLayout) {
-        unsafe {
-            self.components.set_layout(layout, self.entities.as_slice());
+        self.components.set_layout(layout);
+    }
+
+    /// Adds a new group to the layout of this world.
+    ///
+    /// Only the new group is grouped. The other groups keep their entities
+    /// and tracked events.
+    pub fn add_group<G>(&mut self)
+    where
+        G: GroupDescriptor,
+    {
+        self.add_group_dyn(G::COMPONENTS);
+    }
+
+    /// Adds a new group to the layout of this world.
+    ///
+    /// Only the new group is grouped. The other groups keep their entities
+    /// and tracked events.
+    pub fn add_group_dyn(&mut self, components: &[ComponentData]) {
+        let mut layout = self.components.layout.clone();
+        layout.add_group_dyn(components);
+        self.components.set_layout(&layout);
+    }
+
+    /// Removes a group from the layout of this world.
+    ///
+    /// Returns whether the group was present in the layout.
+    pub fn remove_group<G>(&mut self) -> bool
+    where
+        G: GroupDescriptor,
+    {
+        self.remove_group_dyn(G::COMPONENTS)
+    }
+
+    /// Removes a group from the layout of this world.
+    ///
+    /// Returns whether the group was present in the layout.
+    pub fn remove_group_dyn(&mut self, components: &[ComponentData]) -> bool {
+        let mut layout = self.components.layout.clone();
+
+        if !layout.remove_group_dyn(components) {
+            return false;
         }
+
+        self.components.set_layout(&layout);
+        true
+    }
+
+    /// Sorts the grouped components of `G` with the comparator attached to the
+    /// group in the layout, so [`QueryAll::slice`](crate::query::QueryAll::slice)
+    /// returns sorted slices.
+    ///
+    /// The order is kept until entities enter or leave the group.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `G` is not a sorted group.
+    pub fn sort_group<G>(&mut self)
+    where
+        G: GroupDescriptor,
+    {
+        self.sort_group_dyn(G::COMPONENTS);
+    }
+
+    /// Sorts the grouped components of the given group with the comparator
+    /// attached to the group in the layout.
+    ///
+    /// The order is kept until entities enter or leave the group.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the group is not sorted.
+    #[inline]
+    pub fn sort_group_dyn(&mut self, components: &[ComponentData]) {
+        self.components.sort_group(components);
+    }
+
+    /// Starts recording [`GroupEvent`]s for the entities that enter or leave
+    /// the group `G`. The events are retrieved with
+    /// [`drain_group_events`](Self::drain_group_events).
+    ///
+    /// Tracking is kept when the layout changes, unless the family of `G` is
+    /// changed or removed.
+    ///
+    /// Returns whether the group was not already tracked.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `G` is not a group in the layout of this world.
+    pub fn track_group<G>(&mut self) -> bool
+    where
+        G: GroupDescriptor,
+    {
+        self.track_group_dyn(G::COMPONENTS)
+    }
+
+    /// Starts recording [`GroupEvent`]s for the entities that enter or leave
+    /// the given group.
+    ///
+    /// Returns whether the group was not already tracked.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the group is not in the layout of this world.
+    #[inline]
+    pub fn track_group_dyn(&mut self, components: &[ComponentData]) -> bool {
+        self.components.track_group(components)
+    }
+
+    /// Stops recording events for the group `G` and discards the events that
+    /// were not drained.
+    ///
+    /// Returns whether the group was tracked.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `G` is not a group in the layout of this world.
+    pub fn untrack_group<G>(&mut self) -> bool
+    where
+        G: GroupDescriptor,
+    {
+        self.untrack_group_dyn(G::COMPONENTS)
+    }
+
+    /// Stops recording events for the given group and discards the events that
+    /// were not drained.
+    ///
+    /// Returns whether the group was tracked.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the group is not in the layout of this world.
+    #[inline]
+    pub fn untrack_group_dyn(&mut self, components: &[ComponentData]) -> bool {
+        self.components.untrack_group(components)
+    }
+
+    /// Removes and returns the events recorded for the group `G` since the
+    /// last call, in the order in which they happened. Returns no events if
+    /// the group is not tracked.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `G` is not a group in the layout of this world.
+    pub fn drain_group_events<G>(&mut self) -> impl Iterator<Item = GroupEvent> + '_
+    where
+        G: GroupDescriptor,
+    {
+        self.drain_group_events_dyn(G::COMPONENTS)
+    }
+
+    /// Removes and returns the events recorded for the given group since the
+    /// last call, in the order in which they happened.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the group is not in the layout of this world.
+    #[inline]
+    pub fn drain_group_events_dyn(
+        &mut self,
+        components: &[ComponentData],
+    ) -> impl Iterator<Item = GroupEvent> + '_ {
+        self.components.drain_group_events(components)
+    }
+
+    /// Adds a new non-owning group to the layout of this world.
+    ///
+    /// Only the entities in the smallest storage of the group are visited to
+    /// fill it.
+    pub fn add_non_owning_group<G>(&mut self)
+    where
+        G: GroupDescriptor,
+    {
+        self.add_non_owning_group_dyn(G::COMPONENTS);
+    }
+
+    /// Adds a new non-owning group to the layout of this world.
+    ///
+    /// Only the entities in the smallest storage of the group are visited to
+    /// fill it.
+    pub fn add_non_owning_group_dyn(&mut self, components: &[ComponentData]) {
+        let mut layout = self.components.layout.clone();
+        layout.add_non_owning_group_dyn(components);
+        self.components.set_layout(&layout);
+    }
+
+    /// Removes a non-owning group from the layout of this world.
+    ///
+    /// Returns whether the group was present in the layout.
+    pub fn remove_non_owning_group<G>(&mut self) -> bool
+    where
+        G: GroupDescriptor,
+    {
+        self.remove_non_owning_group_dyn(G::COMPONENTS)
+    }
+
+    /// Removes a non-owning group from the layout of this world.
+    ///
+    /// Returns whether the group was present in the layout.
+    pub fn remove_non_owning_group_dyn(&mut self, components: &[ComponentData]) -> bool {
+        let mut layout = self.components.layout.clone();
+
+        if !layout.remove_non_owning_group_dyn(components) {
+            return false;
+        }
+
+        self.components.set_layout(&layout);
+        true
+    }
+
+    /// Returns an iterator over the component groups set on this world, in
+    /// layout order.
+    #[inline]
+    pub fn groups(&self) -> impl Iterator<Item = GroupSummary<'_>> + '_ {
+        self.components.groups()
+    }
+
+    /// Enables or disables the recording of [`QueryStats`] for the
+    /// [`QueryAll`] queries run on this world. Profiling is disabled by
+    /// default.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn set_query_profiling(&mut self, enabled: bool) {
+        self.profiler.enabled = enabled;
+    }
+
+    /// Returns the usage recorded for each combination of query components
+    /// while profiling was enabled, from the most to the least visited
+    /// entities.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn query_stats(&self) -> Vec<QueryStats> {
+        self.profiler.stats()
+    }
+
+    /// Discards all recorded [`QueryStats`].
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn clear_query_stats(&mut self) {
+        self.profiler.clear();
+    }
+
+    /// Proposes a group layout from the recorded [`QueryStats`], so that the
+    /// queries that visited the most entities can be iterated densely.
+    ///
+    /// Queries whose groups would conflict with those of busier queries are
+    /// left ungrouped. The result can be applied with
+    /// [`set_layout`](Self::set_layout).
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn suggest_layout(&self) -> GroupLayout {
+        self.profiler.suggest_layout()
     }
 
     /// Registers a new component type on this world.
@@ -107,6 +380,35 @@ impl World {
         C::extend(self, components)
     }
 
+    /// Inserts the components produced by the iterator to their paired
+    /// entities, overwriting previous data if necessary. Entities missing from
+    /// the world are skipped.
+    ///
+    /// Groups are updated once, after all components were inserted.
+    pub fn insert_batch<C, I>(&mut self, components: I)
+    where
+        C: ComponentSet,
+        I: IntoIterator<Item = (Entity, C)>,
+    {
+        C::insert_batch(self, components);
+    }
+
+    /// Runs `f` with grouping deferred, so inserting components does not move
+    /// entities into groups one at a time. The affected groups are completed in
+    /// one linear pass over their storages once `f` returns, even if it panics.
+    ///
+    /// While grouping is deferred, queries over groups fall back to sparse
+    /// iteration and [`QueryAll::slice`](crate::query::QueryAll::slice) returns
+    /// `None`. Nested batches are completed together with the outermost one.
+    pub fn batch<R>(&mut self, f: impl FnOnce(&mut World) -> R) -> R {
+        if !self.components.defer_grouping() {
+            return f(self);
+        }
+
+        let guard = BatchGuard(self);
+        f(guard.0)
+    }
+
     /// Removes the `entity` and its associated components from the world.
     ///
     /// Returns whether the operation was successfull, i.e. whether the entity
@@ -121,6 +423,34 @@ impl World {
         true
     }
 
+    /// Removes the `entity` from the world, returning its components as a
+    /// type-erased collection.
+    ///
+    /// Returns `None` if the entity did not exist in the world.
+    #[must_use = "Use `destroy` to discard the components."]
+    pub fn take_entity(&mut self, entity: Entity) -> Option<EntityComponents> {
+        if !self.entities.remove(entity) {
+            return None;
+        }
+
+        Some(self.components.take(entity))
+    }
+
+    /// Creates a new entity with the given type-erased `components`.
+    ///
+    /// Returns the newly created entity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the `components` is not registered. The world is left
+    /// unchanged in that case.
+    pub fn spawn_components(&mut self, components: EntityComponents) -> Entity {
+        self.components.ensure_registered_dyn(&components);
+        let entity = self.entities.create();
+        self.components.insert_dyn(entity, components);
+        entity
+    }
+
     /// Queues the creation of an entity without requiring exclusive access to
     /// the world. Entities created with this method can be added to the world
     /// by calling [`maintain`](Self::maintain).
@@ -158,6 +488,22 @@ impl World {
         true
     }
 
+    /// Inserts `components` to an existing `entity`, returning the overwritten
+    /// components as options.
+    ///
+    /// Returns `None` if the `entity` does not exist in the world.
+    #[must_use = "Use `insert` to discard the overwritten components."]
+    pub fn replace<C>(&mut self, entity: Entity, components: C) -> Option<C::Remove>
+    where
+        C: ComponentSet,
+    {
+        if !self.entities.contains(entity) {
+            return None;
+        }
+
+        unsafe { Some(C::replace(self, entity, components)) }
+    }
+
     /// Removes components from the `entity`, returning the removed components
     /// as options.
     #[must_use = "Use `delete` to discard the components."]
@@ -180,8 +526,26 @@ impl World {
         }
     }
 
+    /// Removes all components of type `T` from the world.
+    pub fn clear_component<T>(&mut self)
+    where
+        T: Component,
+    {
+        self.components.clear_component::<T>();
+    }
+
+    /// Removes all components of type `T` from the world, returning them
+    /// along with the entities they belonged to.
+    #[must_use = "Use `clear_component` to discard the components."]
+    pub fn take_component<T>(&mut self) -> Vec<(Entity, T)>
+    where
+        T: Component,
+    {
+        self.components.take_component::<T>()
+    }
+
     /// Queries an entity with the given components.
-    pub fn query_one<G>(&self) -> QueryOne<G, (), ()>
+    pub fn query_one<G>(&self) -> QueryOne<'_, G, (), ()>
     where
         G: Query,
     {
@@ -189,7 +553,7 @@ impl World {
     }
 
     /// Queries all entities with the given components.
-    pub fn query_all<G>(&self) -> QueryAll<G, (), ()>
+    pub fn query_all<G>(&self) -> QueryAll<'_, G, (), ()>
     where
         G: Query,
     {
@@ -243,6 +607,25 @@ impl World {
         self.entities.is_empty()
     }
 
+    /// Returns the number of bytes currently allocated by the component and
+    /// entity storages of this world, as drawn from its allocator.
+    ///
+    /// Returns `None` if the world was not built with
+    /// [`WorldBuilder::set_allocation_tracking`].
+    ///
+    /// Only the component sparse sets, the entity sparse set and the entity sets
+    /// of non-owning groups draw from the world's allocator. The group and group
+    /// event lists, the component lists of non-owning groups, the component
+    /// metadata, the entity id allocator and the values returned by methods
+    /// such as
+    /// [`take_component`](Self::take_component) use the global allocator and
+    /// are not counted.
+    #[inline]
+    #[must_use]
+    pub fn allocated_bytes(&self) -> Option<usize> {
+        self.components.allocator.allocated_bytes()
+    }
+
     /// Removes all entities and components from the world.
     #[inline]
     pub fn clear(&mut self) {
@@ -260,7 +643,7 @@ impl World {
 
     /// Returns a shared view over all components of type `T`.
     #[must_use]
-    pub fn borrow<T>(&self) -> View<T>
+    pub fn borrow<T>(&self) -> View<'_, T>
     where
         T: Component,
     {
@@ -269,7 +652,7 @@ impl World {
 
     /// Returns an exclusive view over all components of type `T`.
     #[must_use]
-    pub fn borrow_mut<T>(&self) -> ViewMut<T>
+    pub fn borrow_mut<T>(&self) -> ViewMut<'_, T>
     where
         T: Component,
     {
@@ -279,7 +662,7 @@ impl World {
     /// Returns a shared view over all components of type `T`, along with
     /// grouping information.
     #[must_use]
-    pub fn borrow_with_group_info<T>(&self) -> (View<T>, Option<GroupInfo>)
+    pub fn borrow_with_group_info<T>(&self) -> (View<'_, T>, Option<GroupInfo>)
     where
         T: Component,
     {
@@ -289,10 +672,26 @@ impl World {
     /// Returns an exclusive view over all components of type `T`, along with
     /// grouping information.
     #[must_use]
-    pub fn borrow_with_group_info_mut<T>(&self) -> (ViewMut<T>, Option<GroupInfo>)
+    pub fn borrow_with_group_info_mut<T>(&self) -> (ViewMut<'_, T>, Option<GroupInfo>)
     where
         T: Component,
     {
         self.components.borrow_with_group_info_mut::<T>()
     }
 }
+
+impl Default for World {
+    #[inline]
+    fn default() -> Self {
+        Self::new(&GroupLayout::default())
+    }
+}
+
+/// Completes the deferred grouping of a world when dropped.
+struct BatchGuard<'a>(&'a mut World);
+
+impl Drop for BatchGuard<'_> {
+    fn drop(&mut self) {
+        self.0.components.finish_deferred_grouping();
+    }
+}
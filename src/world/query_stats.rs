@@ -0,0 +1,190 @@
+use crate::component::{ComponentData, FxHashMap, GroupLayout};
+use crate::query::Query;
+use alloc::vec::Vec;
+use core::cmp::{Ordering, Reverse};
+use std::sync::{Mutex, PoisonError};
+
+/// Usage recorded for one combination of query components while query
+/// profiling is enabled.
+#[derive(Clone, Debug)]
+pub struct QueryStats {
+    /// The component types required by the "get" part of the query.
+    pub get: Vec<ComponentData>,
+    /// The component types required by the "include" filter.
+    pub include: Vec<ComponentData>,
+    /// The component types required by the "exclude" filter.
+    pub exclude: Vec<ComponentData>,
+    /// The number of times the query was iterated.
+    pub runs: u64,
+    /// The total number of entities visited by the query.
+    pub visited: u64,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+struct QueryKey {
+    get: Vec<ComponentData>,
+    include: Vec<ComponentData>,
+    exclude: Vec<ComponentData>,
+}
+
+#[derive(Default, Debug)]
+pub(crate) struct QueryProfiler {
+    pub enabled: bool,
+    stats: Mutex<FxHashMap<QueryKey, (u64, u64)>>,
+}
+
+impl QueryProfiler {
+    /// Records a run of the query that visited `visited` entities, if
+    /// profiling is enabled.
+    #[inline]
+    pub fn record<G, I, E>(&self, visited: usize)
+    where
+        G: Query,
+        I: Query,
+        E: Query,
+    {
+        if self.enabled {
+            self.record_slow::<G, I, E>(visited);
+        }
+    }
+
+    #[inline(never)]
+    fn record_slow<G, I, E>(&self, visited: usize)
+    where
+        G: Query,
+        I: Query,
+        E: Query,
+    {
+        let key = QueryKey {
+            get: required_components::<G>(),
+            include: required_components::<I>(),
+            exclude: required_components::<E>(),
+        };
+
+        let mut stats = self.stats.lock().unwrap_or_else(PoisonError::into_inner);
+        let (runs, total_visited) = stats.entry(key).or_default();
+        *runs += 1;
+        *total_visited += visited as u64;
+    }
+
+    pub fn clear(&mut self) {
+        self.stats
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+
+    /// Returns the recorded stats, ordered by the number of visited entities,
+    /// from highest to lowest. Ties are ordered by the names of the component
+    /// types of the queries, which are also sorted by name.
+    #[must_use]
+    pub fn stats(&self) -> Vec<QueryStats> {
+        let stats = self.stats.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let mut stats = stats
+            .iter()
+            .map(|(key, &(runs, visited))| QueryStats {
+                get: sorted_by_name(&key.get),
+                include: sorted_by_name(&key.include),
+                exclude: sorted_by_name(&key.exclude),
+                runs,
+                visited,
+            })
+            .collect::<Vec<_>>();
+
+        stats.sort_by(|a, b| {
+            b.visited
+                .cmp(&a.visited)
+                .then_with(|| compare_names(&a.get, &b.get))
+                .then_with(|| compare_names(&a.include, &b.include))
+                .then_with(|| compare_names(&a.exclude, &b.exclude))
+        });
+        stats
+    }
+
+    /// Builds a layout from the groups that would let the most visited
+    /// queries be iterated densely. Groups that conflict with the groups of
+    /// busier queries are skipped.
+    #[must_use]
+    pub fn suggest_layout(&self) -> GroupLayout {
+        let mut candidates = Vec::<(Vec<Vec<ComponentData>>, u64)>::new();
+
+        for stats in self.stats() {
+            let mut group = stats.get;
+            group.extend(stats.include);
+            group.sort_unstable();
+            group.dedup();
+
+            if group.len() < 2 {
+                continue;
+            }
+
+            // Excluding components requires a child group that owns them.
+            let groups = if stats.exclude.is_empty() {
+                vec![group]
+            } else {
+                let mut child_group = group.clone();
+                child_group.extend(stats.exclude);
+                child_group.sort_unstable();
+                child_group.dedup();
+                vec![group, child_group]
+            };
+
+            if let Some((_, visited)) = candidates.iter_mut().find(|(g, _)| *g == groups) {
+                *visited += stats.visited;
+            } else {
+                candidates.push((groups, stats.visited));
+            }
+        }
+
+        candidates.sort_by_key(|&(_, visited)| Reverse(visited));
+
+        let mut layout = GroupLayout::default();
+
+        for (groups, _) in candidates {
+            let mut new_layout = layout.clone();
+
+            let fits = groups.iter().all(|group| {
+                let fits = new_layout.can_add_group_dyn(group);
+
+                if fits {
+                    new_layout.add_group_dyn(group);
+                }
+
+                fits
+            });
+
+            if fits {
+                layout = new_layout;
+            }
+        }
+
+        layout
+    }
+}
+
+#[must_use]
+fn required_components<Q>() -> Vec<ComponentData>
+where
+    Q: Query,
+{
+    let mut components = Vec::new();
+    Q::add_required_components(&mut components);
+    components.sort_unstable();
+    components.dedup();
+    components
+}
+
+#[must_use]
+fn sorted_by_name(components: &[ComponentData]) -> Vec<ComponentData> {
+    let mut components = components.to_vec();
+    components.sort_unstable_by_key(ComponentData::type_name);
+    components
+}
+
+#[must_use]
+fn compare_names(a: &[ComponentData], b: &[ComponentData]) -> Ordering {
+    a.iter()
+        .map(ComponentData::type_name)
+        .cmp(b.iter().map(ComponentData::type_name))
+}
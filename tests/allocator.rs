@@ -0,0 +1,79 @@
+mod common;
+
+use common::*;
+use sparsey::allocator::{Allocator, Global};
+use sparsey::World;
+use std::alloc::Layout;
+use std::ptr::NonNull;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[derive(Clone, Default)]
+struct CountingAllocator {
+    allocated: Arc<AtomicUsize>,
+}
+
+unsafe impl Allocator for CountingAllocator {
+    fn allocate(&self, layout: Layout) -> Option<NonNull<u8>> {
+        self.allocated.fetch_add(layout.size(), Ordering::Relaxed);
+        Global.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        self.allocated.fetch_sub(layout.size(), Ordering::Relaxed);
+        Global.deallocate(ptr, layout);
+    }
+}
+
+#[test]
+fn test_custom_allocator() {
+    let allocator = CountingAllocator::default();
+
+    let mut world = World::builder()
+        .add_group::<(A, B)>()
+        .register::<C>()
+        .set_allocator(allocator.clone())
+        .set_allocation_tracking(true)
+        .build();
+
+    assert_eq!(allocator.allocated.load(Ordering::Relaxed), 0);
+
+    for i in 0..100 {
+        world.create((A(i), B(i), C(i)));
+    }
+
+    assert!(allocator.allocated.load(Ordering::Relaxed) > 0);
+    assert_eq!(
+        world.allocated_bytes(),
+        Some(allocator.allocated.load(Ordering::Relaxed)),
+    );
+    assert_eq!(world.query_all::<(&A, &B, &C)>().iter().count(), 100);
+
+    drop(world);
+    assert_eq!(allocator.allocated.load(Ordering::Relaxed), 0);
+}
+
+#[test]
+fn test_allocated_bytes() {
+    let mut builder = World::builder();
+    builder
+        .add_group::<(A, B)>()
+        .register::<C>()
+        .set_allocation_tracking(true);
+
+    let mut world = builder.build();
+    let other = builder.build();
+    assert_eq!(world.allocated_bytes(), Some(0));
+
+    for i in 0..100 {
+        world.create((A(i), B(i), C(i)));
+    }
+
+    assert!(world.allocated_bytes().unwrap() >= 100 * 3 * size_of::<u32>());
+    assert_eq!(other.allocated_bytes(), Some(0));
+
+    let mut world = World::default();
+    world.register::<A>();
+    world.create((A(0),));
+    assert_eq!(world.allocated_bytes(), None);
+}
@@ -0,0 +1,43 @@
+mod common;
+
+use common::*;
+use sparsey::World;
+
+#[test]
+fn test_batch() {
+    let mut world = World::builder()
+        .add_group::<(A, B)>()
+        .add_group::<(A, B, C)>()
+        .add_non_owning_group::<(D, E)>()
+        .build();
+
+    let e0 = world.create((A(0), B(0), C(0)));
+
+    let entities = world.batch(|world| {
+        let entities = world.extend((1..100).map(|i| (A(i), B(i), C(i), D(i), E(i))));
+        let entities = entities.to_vec();
+
+        world.delete::<(B,)>(entities[0]);
+        world.insert(e0, (D(0), E(0)));
+
+        // Queries stay correct while grouping is deferred.
+        assert!(world.query_all::<(&A, &B, &C)>().slice().is_none());
+        assert_eq!(world.query_all::<(&A, &B, &C)>().iter().count(), 99);
+
+        world.batch(|world| {
+            world.create((A(100), B(100)));
+        });
+
+        assert!(world.query_all::<(&A, &B)>().slice().is_none());
+        entities
+    });
+
+    let mut query = world.query_all::<(&A, &B, &C)>();
+    let (a, _, _) = query.slice().unwrap();
+    assert_eq!(a.len(), 99);
+    assert!(!a.contains(&A(1)));
+
+    assert_eq!(world.query_all::<(&A, &B)>().slice().unwrap().0.len(), 100);
+    assert_eq!(world.query_all::<(&D, &E)>().iter().count(), 100);
+    assert!(world.contains::<(&A, &C)>(entities[0]));
+}
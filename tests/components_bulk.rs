@@ -0,0 +1,56 @@
+mod common;
+
+use common::*;
+use sparsey::entity::Entity;
+use sparsey::World;
+
+#[test]
+fn test_components_bulk() {
+    let mut world = World::builder()
+        .add_group::<(A, B)>()
+        .add_group::<(A, B, C)>()
+        .build();
+
+    let e0 = world.create((A(0), B(0), C(0)));
+    let e1 = world.create((A(1), B(1)));
+    let e2 = world.create((A(2),));
+
+    // Clear a grouped component
+    world.clear_component::<C>();
+    assert!(!world.contains::<&C>(e0));
+    assert!(world.query_all::<(&A, &B, &C)>().iter().is_dense());
+    assert_eq!(world.query_all::<(&A, &B, &C)>().iter().count(), 0);
+    assert_eq!(world.query_all::<(&A, &B)>().iter().count(), 2);
+
+    // Insert components in bulk, skipping missing entities
+    let missing = Entity::with_index(100);
+    world.insert_batch([
+        (e1, (B(11), C(11))),
+        (e2, (B(12), C(12))),
+        (missing, (B(0), C(0))),
+    ]);
+    assert!(!world.contains_entity(missing));
+    assert_eq!(world.query_all::<(&A, &B)>().iter().count(), 3);
+
+    let mut entities = world
+        .query_all::<(Entity, &A, &B, &C)>()
+        .iter()
+        .map(|(entity, _, _, &c)| (entity, c))
+        .collect::<Vec<_>>();
+
+    entities.sort();
+    assert_eq!(entities, [(e1, C(11)), (e2, C(12))]);
+
+    // Take a grouped component
+    let mut taken = world.take_component::<B>();
+    taken.sort();
+    assert_eq!(taken, [(e0, B(0)), (e1, B(11)), (e2, B(12))]);
+    assert!(world.borrow::<B>().is_empty());
+    assert_eq!(world.query_all::<(&A, &B)>().iter().count(), 0);
+    assert_eq!(world.query_all::<(&A, &B, &C)>().iter().count(), 0);
+
+    // Groups are rebuilt after the components are added back
+    world.insert_batch(taken.into_iter().map(|(entity, b)| (entity, (b,))));
+    assert_eq!(world.query_all::<(&A, &B)>().iter().count(), 3);
+    assert_eq!(world.query_all::<(&A, &B, &C)>().iter().count(), 2);
+}
@@ -51,3 +51,34 @@ fn test_components_crud() {
     assert_eq!(world.query_one::<&A>().get(e0), None);
     assert_eq!(world.query_one::<&B>().get(e0), None);
 }
+
+#[test]
+fn test_components_replace() {
+    let mut world = World::builder()
+        .add_group::<(A, B)>()
+        .register::<C>()
+        .build();
+
+    let e0 = world.create((A(0),));
+
+    // Replace existing and missing components
+    assert_eq!(world.replace(e0, (A(1), B(1))), Some((Some(A(0)), None)));
+    assert_eq!(world.query_one::<(&A, &B)>().get(e0), Some((&A(1), &B(1))));
+    assert!(world.query_all::<(&A, &B)>().iter().is_dense());
+    assert_eq!(world.query_all::<(&A, &B)>().iter().count(), 1);
+
+    // Replace all components
+    assert_eq!(
+        world.replace(e0, (A(2), B(2), C(2))),
+        Some((Some(A(1)), Some(B(1)), None)),
+    );
+    assert_eq!(
+        world.query_one::<(&A, &B, &C)>().get(e0),
+        Some((&A(2), &B(2), &C(2))),
+    );
+
+    // Replacing components on a missing entity fails
+    world.destroy(e0);
+    assert_eq!(world.replace(e0, (A(3),)), None);
+    assert!(!world.contains::<&A>(e0));
+}
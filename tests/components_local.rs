@@ -0,0 +1,112 @@
+mod common;
+
+use common::*;
+use sparsey::entity::Entity;
+use sparsey::world::LocalWorld;
+use sparsey::World;
+use std::rc::Rc;
+
+#[test]
+fn test_components_local() {
+    let mut world = LocalWorld::default();
+    assert!(!world.is_local_registered::<Rc<u32>>());
+    assert!(world.register_local::<Rc<u32>>());
+    assert!(!world.register_local::<Rc<u32>>());
+    assert!(world.is_local_registered::<Rc<u32>>());
+
+    let handle = Rc::new(0_u32);
+    let e0 = world.create(());
+    let e1 = world.create(());
+
+    // Insert local components
+    assert!(world.insert_local(e0, Rc::clone(&handle)));
+    assert!(world.insert_local(e1, Rc::clone(&handle)));
+    assert_eq!(Rc::strong_count(&handle), 3);
+    assert_eq!(world.borrow_local::<Rc<u32>>().len(), 2);
+
+    // Remove local component
+    assert_eq!(world.remove_local::<Rc<u32>>(e0).as_deref(), Some(&0));
+    assert_eq!(world.remove_local::<Rc<u32>>(e0), None);
+    assert_eq!(Rc::strong_count(&handle), 2);
+
+    // Local components are dropped when their entity is destroyed
+    world.destroy(e1);
+    assert!(world.borrow_local::<Rc<u32>>().is_empty());
+    assert_eq!(Rc::strong_count(&handle), 1);
+    assert!(!world.insert_local(e1, Rc::clone(&handle)));
+    assert_eq!(Rc::strong_count(&handle), 1);
+}
+
+#[test]
+fn test_local_world_inner() {
+    let mut world = World::builder().register::<A>().build();
+    let e0 = world.create((A(0),));
+
+    let mut world = LocalWorld::from(world);
+    world.register_local::<Rc<u32>>();
+
+    let handle = Rc::new(0_u32);
+    let e1 = world.create((A(1),));
+    world.insert_local(e0, Rc::clone(&handle));
+    world.insert_local(e1, Rc::clone(&handle));
+    assert_eq!(world.query_all::<&A>().count(), 2);
+
+    let components = world.take_entity(e0).unwrap();
+    assert_eq!(components.len(), 1);
+    assert_eq!(Rc::strong_count(&handle), 2);
+
+    world.clear();
+    assert_eq!(Rc::strong_count(&handle), 1);
+
+    let e2 = world.create(());
+    world.insert_local(e2, Rc::clone(&handle));
+    assert_eq!(Rc::strong_count(&handle), 2);
+
+    let world = world.into_inner();
+    assert_eq!(world.entities().len(), 1);
+    assert_eq!(Rc::strong_count(&handle), 1);
+}
+
+#[test]
+fn test_local_world_batch() {
+    let mut world = LocalWorld::from(World::builder().add_group::<(A, B)>().build());
+    world.register_local::<Rc<u32>>();
+
+    let handle = Rc::new(0_u32);
+    let e0 = world.create((A(0), B(0)));
+    let e1 = world.create((A(1),));
+    world.insert_local(e0, Rc::clone(&handle));
+    world.insert_local(e1, Rc::clone(&handle));
+
+    // Entities removed in a batch lose their local components
+    world.batch(|world| {
+        world.insert(e1, (B(1),));
+        world.destroy(e0);
+    });
+
+    assert_eq!(Rc::strong_count(&handle), 2);
+    assert_eq!(world.borrow_local::<Rc<u32>>().entities(), [e1]);
+    assert!(world.query_all::<(&A, &B)>().explain().is_dense());
+}
+
+#[test]
+fn test_local_world_queries() {
+    let mut world = LocalWorld::from(World::builder().register::<A>().build());
+    world.register_local::<Rc<u32>>();
+
+    let e0 = world.create((A(0),));
+    let e1 = world.create(());
+    let e2 = world.create((A(2),));
+    world.insert_local(e0, Rc::new(0_u32));
+    world.insert_local(e1, Rc::new(1_u32));
+    world.insert_local(e2, Rc::new(2_u32));
+
+    world.for_each_local_mut::<Rc<u32>, &A>(|local, a| {
+        *local = Rc::new(**local + a.0 + 10);
+    });
+
+    let mut items = Vec::new();
+    world.for_each_local::<Rc<u32>, Entity>(|local, entity| items.push((entity, **local)));
+    items.sort_unstable();
+    assert_eq!(items, [(e0, 10), (e1, 1), (e2, 14)]);
+}
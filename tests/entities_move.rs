@@ -0,0 +1,82 @@
+mod common;
+
+use common::*;
+use sparsey::component::EntityComponents;
+use sparsey::World;
+use std::panic::{self, AssertUnwindSafe};
+
+#[test]
+fn test_entities_move() {
+    let mut world = World::builder()
+        .add_group::<(A, B)>()
+        .register::<C>()
+        .build();
+
+    let mut staging = World::builder()
+        .register::<A>()
+        .register::<B>()
+        .register::<C>()
+        .build();
+
+    let e0 = world.create((A(0), B(0), C(0)));
+    let e1 = world.create((A(1), B(1)));
+
+    // Take entity from the world
+    let components = world.take_entity(e0).unwrap();
+    assert!(!world.contains_entity(e0));
+    assert_eq!(components.len(), 3);
+    assert_eq!(components.get::<A>(), Some(&A(0)));
+    assert_eq!(components.get::<B>(), Some(&B(0)));
+    assert_eq!(components.get::<C>(), Some(&C(0)));
+    assert_eq!(world.query_all::<(&A, &B)>().iter().count(), 1);
+    assert!(world.take_entity(e0).is_none());
+
+    // Move entity to another world
+    let s0 = staging.spawn_components(components);
+    assert_eq!(
+        staging.query_one::<(&A, &B, &C)>().get(s0),
+        Some((&A(0), &B(0), &C(0))),
+    );
+
+    // Move entity back to the original world
+    let mut components = staging.take_entity(s0).unwrap();
+    assert_eq!(components.remove::<C>(), Some(C(0)));
+    assert_eq!(components.insert(A(2)), Some(A(0)));
+    assert!(staging.is_empty());
+
+    let e2 = world.spawn_components(components);
+    assert_eq!(world.query_one::<(&A, &B)>().get(e2), Some((&A(2), &B(0))));
+    assert!(!world.contains::<&C>(e2));
+    assert!(world.query_all::<(&A, &B)>().iter().is_dense());
+    assert_eq!(world.query_all::<(&A, &B)>().iter().count(), 2);
+    assert!(world.contains_entity(e1));
+
+    // Empty collections create entities without components
+    let e3 = world.spawn_components(EntityComponents::new());
+    assert!(world.contains_entity(e3));
+}
+
+#[test]
+#[should_panic(expected = "was not registered")]
+fn test_spawn_unregistered_components() {
+    let mut world = World::builder().add_group::<(A, B)>().build();
+    let e0 = world.create((A(0), B(0)));
+
+    let mut components = EntityComponents::new();
+    components.insert(A(1));
+    components.insert(B(1));
+    components.insert(C(1));
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| world.spawn_components(components)));
+
+    // The world is unchanged by the failed spawn.
+    assert_eq!(world.entities(), [e0]);
+    assert_eq!(world.borrow::<A>().len(), 1);
+    assert_eq!(world.borrow::<B>().len(), 1);
+    assert!(world.query_all::<(&A, &B)>().explain().is_dense());
+    assert_eq!(world.query_all::<(&A, &B)>().iter().count(), 1);
+
+    if let Err(payload) = result {
+        panic::resume_unwind(payload);
+    }
+}
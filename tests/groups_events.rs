@@ -0,0 +1,114 @@
+mod common;
+
+use common::*;
+use sparsey::component::{GroupEvent, GroupLayout};
+use sparsey::World;
+
+#[test]
+fn test_group_events() {
+    let mut world = World::builder()
+        .add_group::<(A, B)>()
+        .add_group::<(A, B, C)>()
+        .build();
+
+    assert!(world.track_group::<(A, B, C)>());
+    assert!(!world.track_group::<(A, B, C)>());
+
+    let e0 = world.create((A(0), B(0)));
+    let e1 = world.create((A(1), B(1), C(1)));
+    world.insert(e0, (C(0),));
+    world.delete::<(B,)>(e1);
+    world.insert(e1, (B(1),));
+    world.destroy(e0);
+
+    assert_eq!(
+        world.drain_group_events::<(A, B, C)>().collect::<Vec<_>>(),
+        [
+            GroupEvent::Entered(e1),
+            GroupEvent::Entered(e0),
+            GroupEvent::Left(e1),
+            GroupEvent::Entered(e1),
+            GroupEvent::Left(e0),
+        ],
+    );
+    assert_eq!(world.drain_group_events::<(A, B, C)>().count(), 0);
+
+    // Untracked groups record nothing.
+    assert_eq!(world.drain_group_events::<(A, B)>().count(), 0);
+
+    // Tracking survives changes to other families.
+    world.add_group::<(D, E)>();
+    world.clear_component::<C>();
+    assert_eq!(
+        world.drain_group_events::<(A, B, C)>().collect::<Vec<_>>(),
+        [GroupEvent::Left(e1)],
+    );
+
+    assert!(world.untrack_group::<(A, B, C)>());
+    world.insert(e1, (C(1),));
+    assert_eq!(world.drain_group_events::<(A, B, C)>().count(), 0);
+}
+
+#[test]
+fn test_group_events_layout_changes() {
+    let mut world = World::builder()
+        .add_group::<(A, B)>()
+        .add_group::<(A, B, C, D)>()
+        .build();
+
+    assert!(world.track_group::<(A, B, C, D)>());
+
+    let e0 = world.create((A(0), B(0), C(0), D(0)));
+    let e1 = world.create((A(1), B(1), C(1)));
+    world.create((A(2), B(2), D(2)));
+    world.create((A(3), B(3)));
+
+    assert_eq!(
+        world
+            .drain_group_events::<(A, B, C, D)>()
+            .collect::<Vec<_>>(),
+        [GroupEvent::Entered(e0)],
+    );
+
+    // Adding a group to the family keeps the tracked group and its entities.
+    let mut layout = GroupLayout::default();
+    layout.add_group::<(A, B)>();
+    layout.add_group::<(A, B, C)>();
+    layout.add_group::<(A, B, C, D)>();
+    world.set_layout(&layout);
+    assert_eq!(world.drain_group_events::<(A, B, C, D)>().count(), 0);
+
+    let query = world.query_all::<(&A, &B, &C)>();
+    assert!(query.explain().is_dense());
+    assert_eq!(query.count(), 2);
+    drop(query);
+
+    let query = world.query_all::<(&A, &B, &C)>().exclude::<&D>();
+    assert!(query.explain().is_dense());
+    assert_eq!(query.entities().collect::<Vec<_>>(), [e1]);
+    drop(query);
+
+    let query = world.query_all::<(&A, &B)>().exclude::<&C>();
+    assert!(query.explain().is_dense());
+    assert_eq!(query.count(), 2);
+    drop(query);
+
+    world.insert(e1, (D(1),));
+    assert_eq!(
+        world
+            .drain_group_events::<(A, B, C, D)>()
+            .collect::<Vec<_>>(),
+        [GroupEvent::Entered(e1)],
+    );
+
+    // Removing a group from the family keeps tracking the others.
+    assert!(world.remove_group::<(A, B, C)>());
+    world.delete::<(D,)>(e0);
+    assert_eq!(
+        world
+            .drain_group_events::<(A, B, C, D)>()
+            .collect::<Vec<_>>(),
+        [GroupEvent::Left(e0)],
+    );
+    assert_eq!(world.query_all::<(&A, &B, &C, &D)>().count(), 1);
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use common::*;
+use sparsey::component::ComponentData;
+use sparsey::World;
+
+#[test]
+fn test_groups_summary() {
+    let mut world = World::builder()
+        .add_group::<(A, B)>()
+        .add_group::<(A, B, C)>()
+        .add_group::<(D, E)>()
+        .build();
+
+    world.create((A(0), B(0)));
+    world.create((A(1), B(1), C(1)));
+    world.create((D(2), E(2)));
+
+    let groups = world.groups().collect::<Vec<_>>();
+    assert_eq!(groups.len(), 3);
+
+    assert_eq!(groups[0].index(), 0);
+    assert_eq!(groups[0].family(), 0);
+    assert_eq!(groups[0].arity(), 2);
+    assert_eq!(groups[0].len(), 2);
+    assert!(groups[0].components().contains(&ComponentData::new::<A>()));
+    assert!(groups[0].components().contains(&ComponentData::new::<B>()));
+
+    assert_eq!(groups[1].index(), 1);
+    assert_eq!(groups[1].family(), 0);
+    assert_eq!(groups[1].arity(), 3);
+    assert_eq!(groups[1].len(), 1);
+    assert!(groups[1]
+        .component_names()
+        .any(|name| name == std::any::type_name::<C>()));
+
+    assert_eq!(groups[2].index(), 2);
+    assert_eq!(groups[2].family(), 1);
+    assert_eq!(groups[2].arity(), 2);
+    assert_eq!(groups[2].len(), 1);
+
+    world.clear();
+    assert!(world.groups().all(|group| group.is_empty()));
+}
+
+#[test]
+fn test_groups_add_remove() {
+    let mut world = World::builder()
+        .add_group::<(D, E)>()
+        .register::<A>()
+        .register::<B>()
+        .register::<C>()
+        .build();
+
+    for i in 0..10 {
+        world.create((A(i), B(i)));
+        world.create((A(i), B(i), C(i)));
+        world.create((D(i), E(i)));
+    }
+
+    world.add_group::<(A, B)>();
+    world.add_group::<(A, B, C)>();
+
+    let lens = world.groups().map(|g| g.len()).collect::<Vec<_>>();
+    assert_eq!(lens, [10, 20, 10]);
+    assert!(world.query_all::<(&A, &B)>().slice().is_some());
+    assert!(world.query_all::<(&A, &B, &C)>().slice().is_some());
+
+    let mut items = world
+        .query_all::<(&A, &B, &C)>()
+        .iter()
+        .map(|(a, b, c)| (*a, *b, *c))
+        .collect::<Vec<_>>();
+
+    items.sort();
+    assert_eq!(
+        items,
+        (0..10).map(|i| (A(i), B(i), C(i))).collect::<Vec<_>>()
+    );
+
+    assert!(world.remove_group::<(A, B)>());
+    assert!(!world.remove_group::<(A, B)>());
+
+    let lens = world.groups().map(|g| g.len()).collect::<Vec<_>>();
+    assert_eq!(lens, [10, 10]);
+    assert!(world.query_all::<(&A, &B)>().slice().is_none());
+    assert!(world.query_all::<(&A, &B, &C)>().slice().is_some());
+
+    assert!(world.remove_group::<(A, B, C)>());
+    assert!(world.remove_group::<(D, E)>());
+    assert_eq!(world.groups().count(), 0);
+    assert_eq!(world.query_all::<(&A, &B)>().iter().count(), 20);
+}
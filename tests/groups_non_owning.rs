@@ -0,0 +1,150 @@
+mod common;
+
+use common::*;
+use sparsey::query::{DenseRejection, PreparedQuery, QueryPlan};
+use sparsey::World;
+use std::collections::HashSet;
+
+#[test]
+fn test_non_owning_groups() {
+    let mut world = World::builder()
+        .add_group::<(A, B)>()
+        .add_non_owning_group::<(B, C)>()
+        .add_non_owning_group::<(A, C)>()
+        .register::<C>()
+        .build();
+
+    let e0 = world.create((A(0), B(0), C(0)));
+    let e1 = world.create((B(1), C(1)));
+    let e2 = world.create((A(2), C(2)));
+    let e3 = world.create((A(3), B(3)));
+
+    // Add unrelated entities so the group lists are the shortest.
+    world.extend((0..10).map(|i| (A(i), B(i))));
+    world.extend((0..10).map(|i| (C(i),)));
+
+    let entities = world
+        .query_all::<(sparsey::Entity, &B, &C)>()
+        .iter()
+        .map(|(e, _, _)| e)
+        .collect::<HashSet<_>>();
+
+    assert_eq!(entities, HashSet::from([e0, e1]));
+
+    world.delete::<(C,)>(e0);
+    world.insert(e3, (C(3),));
+    world.destroy(e1);
+
+    let entities = world
+        .query_all::<(sparsey::Entity, &mut B)>()
+        .include::<&C>()
+        .iter()
+        .map(|(e, _)| e)
+        .collect::<HashSet<_>>();
+
+    assert_eq!(entities, HashSet::from([e3]));
+
+    let entities = world
+        .query_all::<(sparsey::Entity, &A, &C)>()
+        .iter()
+        .map(|(e, _, _)| e)
+        .collect::<HashSet<_>>();
+
+    assert_eq!(entities, HashSet::from([e2, e3]));
+
+    let entities = world
+        .query_all::<(sparsey::Entity, &A, Option<&B>, &C)>()
+        .exclude::<&B>()
+        .iter()
+        .map(|(e, _, _, _)| e)
+        .collect::<HashSet<_>>();
+
+    assert_eq!(entities, HashSet::from([e2]));
+}
+
+#[test]
+fn test_non_owning_groups_layout_changes() {
+    let mut world = World::builder()
+        .register::<A>()
+        .register::<B>()
+        .register::<C>()
+        .build();
+
+    let e0 = world.create((A(0), B(0), C(0)));
+    let _ = world.create((A(1), C(1)));
+    world.extend((0..10).map(|i| (B(i),)));
+
+    world.add_non_owning_group::<(A, B)>();
+    world.add_group::<(B, C)>();
+
+    let entities = world
+        .query_all::<(sparsey::Entity, &A, &B)>()
+        .iter()
+        .map(|(e, _, _)| e)
+        .collect::<Vec<_>>();
+
+    assert_eq!(entities, [e0]);
+
+    world.clear_component::<A>();
+    assert_eq!(world.query_all::<(&A, &B)>().iter().count(), 0);
+
+    world.insert_batch([(e0, (A(5),))]);
+    assert_eq!(world.query_all::<(&A, &B)>().iter().count(), 1);
+
+    assert!(world.remove_non_owning_group::<(A, B)>());
+    assert!(!world.remove_non_owning_group::<(A, B)>());
+    assert_eq!(world.query_all::<(&A, &B)>().iter().count(), 1);
+}
+
+#[test]
+fn test_non_owning_groups_exact_match() {
+    let mut world = World::builder()
+        .add_non_owning_group::<(A, B)>()
+        .register::<C>()
+        .build();
+
+    let e0 = world.create((A(0), B(0)));
+    let e1 = world.create((A(1), B(1), C(1)));
+    world.extend((0..10).map(|i| (A(i),)));
+    world.extend((0..10).map(|i| (B(i),)));
+
+    let plan = world.query_all::<(&A, &B)>().explain();
+    assert_eq!(
+        plan,
+        QueryPlan::NonOwningGroup {
+            len: 2,
+            rejection: DenseRejection::NoGroup,
+        },
+    );
+
+    let query = world.query_all::<(sparsey::Entity, &A)>().include::<&B>();
+    assert!(query.explain().is_non_owning_group());
+    assert_eq!(query.count(), 2);
+
+    let mut query = world
+        .query_all::<(sparsey::Entity, &A, &B)>()
+        .exclude::<&C>();
+
+    assert!(query.explain().is_non_owning_group());
+    assert_eq!(query.count(), 1);
+
+    let entities = query.iter().map(|(e, _, _)| e).collect::<Vec<_>>();
+    assert_eq!(entities, [e0]);
+
+    let entities = world
+        .query_all::<(sparsey::Entity, &A, &B)>()
+        .iter()
+        .rev()
+        .map(|(e, _, _)| e)
+        .collect::<Vec<_>>();
+
+    assert_eq!(entities, [e1, e0]);
+
+    // Queries that require more components only use the group as a driver.
+    assert!(world.query_all::<(&A, &B, &C)>().explain().is_sparse());
+
+    // Prepared queries keep the matched group.
+    let mut prepared = PreparedQuery::<(&A, &B)>::new(&world);
+    assert!(prepared.run(&world).explain().is_non_owning_group());
+    assert_eq!(prepared.run(&world).count(), 2);
+}
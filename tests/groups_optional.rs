@@ -0,0 +1,42 @@
+mod common;
+
+use common::*;
+use sparsey::entity::Entity;
+use sparsey::World;
+
+#[test]
+fn test_optional_parts_keep_dense_iteration() {
+    let mut world = World::builder()
+        .add_group::<(A, B)>()
+        .register::<C>()
+        .build();
+
+    let e0 = world.create((A(0), B(0), C(0)));
+    let e1 = world.create((A(1), B(1)));
+    world.create((A(2), C(2)));
+
+    let mut query = world.query_all::<(Entity, &A, &B, Option<&C>)>();
+    assert!(query.iter().is_dense());
+    assert!(query.slice().is_some());
+
+    let mut items = query.iter().collect::<Vec<_>>();
+    items.sort_by_key(|(entity, ..)| *entity);
+    assert_eq!(
+        items,
+        [(e0, &A(0), &B(0), Some(&C(0))), (e1, &A(1), &B(1), None),],
+    );
+    drop(query);
+
+    world.for_each::<(&mut A, &B, Option<&mut C>)>(|(a, b, c)| {
+        if let Some(c) = c {
+            c.0 += a.0 + b.0 + 10;
+        }
+        a.0 += 10;
+    });
+
+    assert_eq!(world.query_one::<&C>().get(e0), Some(&C(10)));
+
+    let mut query = world.query_all::<(&A, Option<&mut C>)>().include::<&B>();
+    assert!(query.iter().is_dense());
+    assert_eq!(query.iter().count(), 2);
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use common::*;
+use sparsey::component::GroupLayout;
+use sparsey::World;
+
+#[test]
+fn test_sorted_group() {
+    let mut world = World::builder()
+        .add_group::<(A, B)>()
+        .add_sorted_group::<(A, B, C), C>(|c1, c2| c2.cmp(c1))
+        .build();
+
+    for i in [3, 7, 1, 9, 4, 0, 8] {
+        world.create((A(i), B(i), C(i)));
+        world.create((A(i), B(i)));
+    }
+
+    world.sort_group::<(A, B, C)>();
+
+    let mut query = world.query_all::<(&A, &B, &C)>();
+    let (a, b, c) = query.slice().unwrap();
+    assert_eq!(c, [C(9), C(8), C(7), C(4), C(3), C(1), C(0)]);
+    assert!(a.iter().zip(c).all(|(a, c)| a.0 == c.0));
+    assert!(b.iter().zip(c).all(|(b, c)| b.0 == c.0));
+    drop(query);
+
+    // Adding an unrelated group keeps the sorted family and its order.
+    world.add_group::<(D, E)>();
+    let mut query = world.query_all::<&C>().include::<(&A, &B)>();
+    let c = query.slice().unwrap();
+    assert_eq!(c, [C(9), C(8), C(7), C(4), C(3), C(1), C(0)]);
+    drop(query);
+
+    // The parent group must still be intact.
+    assert_eq!(world.query_all::<(&A, &B)>().slice().unwrap().0.len(), 14);
+    assert_eq!(
+        world
+            .query_all::<(&A, &B)>()
+            .exclude::<&C>()
+            .slice()
+            .unwrap()
+            .0
+            .len(),
+        7,
+    );
+}
+
+#[test]
+fn test_sorted_group_by_parent_component() {
+    let mut world = World::builder()
+        .add_group::<(A, B)>()
+        .add_sorted_group::<(A, B, C), A>(A::cmp)
+        .build();
+
+    for i in [3, 7, 1, 9, 4, 0, 8] {
+        world.create((A(i), B(i), C(i)));
+        world.create((A(i + 10), B(i)));
+    }
+
+    world.sort_group::<(A, B, C)>();
+
+    let mut query = world.query_all::<(&A, &B, &C)>();
+    let (a, b, c) = query.slice().unwrap();
+    assert_eq!(a, [A(0), A(1), A(3), A(4), A(7), A(8), A(9)]);
+    assert!(a.iter().zip(b).all(|(a, b)| a.0 == b.0));
+    assert!(a.iter().zip(c).all(|(a, c)| a.0 == c.0));
+
+    // Entities without `C` must not be moved into the sorted group.
+    let mut query = world.query_all::<&A>().include::<&B>().exclude::<&C>();
+    let a = query.slice().unwrap();
+    assert_eq!(a.len(), 7);
+    assert!(a.iter().all(|a| a.0 >= 10));
+}
+
+#[test]
+#[should_panic(expected = "can be sorted")]
+fn test_sorted_group_not_largest() {
+    let mut layout = GroupLayout::default();
+    layout.add_sorted_group::<(A, B), A>(A::cmp);
+    layout.add_group::<(A, B, C)>();
+}
@@ -5,7 +5,6 @@ use sparsey::entity::Entity;
 use sparsey::query::Query;
 use sparsey::World;
 use std::collections::HashSet;
-use std::iter::FromIterator;
 
 #[test]
 fn test_sparse() {
@@ -62,6 +61,6 @@ where
     let entities = iter.collect::<HashSet<_>>();
     assert_eq!(
         entities,
-        HashSet::from_iter(expected_entities.iter().copied()),
+        expected_entities.iter().copied().collect::<HashSet<_>>(),
     );
 }
@@ -0,0 +1,75 @@
+mod common;
+
+use common::*;
+use sparsey::entity::Entity;
+use sparsey::query::SingleError;
+use sparsey::World;
+
+#[test]
+fn test_query_aggregates() {
+    let mut world = World::builder()
+        .add_group::<(A, B)>()
+        .register::<C>()
+        .build();
+
+    let e0 = world.create((A(0), B(0)));
+    let e1 = world.create((A(1), B(1), C(1)));
+    let e2 = world.create((A(2), C(2)));
+
+    let query = world.query_all::<(&A, &B)>();
+    assert!(query.explain().is_dense());
+    assert_eq!(query.count(), 2);
+    assert!(!query.is_empty());
+    let mut entities = query.entities().collect::<Vec<_>>();
+    entities.sort();
+    assert_eq!(entities, [e0, e1]);
+    drop(query);
+
+    let query = world.query_all::<&A>().include::<&C>();
+    assert_eq!(query.count(), 2);
+    let mut entities = query.entities().collect::<Vec<_>>();
+    entities.sort();
+    assert_eq!(entities, [e1, e2]);
+    drop(query);
+
+    let query = world.query_all::<&B>().exclude::<&A>();
+    assert_eq!(query.count(), 0);
+    assert!(query.is_empty());
+    assert_eq!(query.entities().next(), None);
+    drop(query);
+
+    let mut query = world.query_all::<(Entity, &mut A)>().exclude::<&B>();
+    let (entity, a) = query.first().unwrap();
+    assert_eq!(entity, e2);
+    a.0 = 20;
+    drop(query);
+
+    let mut query = world.query_all::<&A>().exclude::<&B>();
+    assert_eq!(query.first(), Some(&A(20)));
+    assert_eq!(query.first(), Some(&A(20)));
+}
+
+#[test]
+fn test_query_single() {
+    let mut world = World::builder().register::<A>().register::<B>().build();
+
+    world.create((A(0),));
+    assert_eq!(
+        world.query_all::<&A>().include::<&B>().single().err(),
+        Some(SingleError::NoMatches)
+    );
+
+    let player = world.create((A(1), B(1)));
+    let mut query = world.query_all::<(Entity, &mut A)>().include::<&B>();
+    let (entity, a) = query.single().unwrap();
+    assert_eq!(entity, player);
+    a.0 += 1;
+    drop(query);
+
+    world.create((A(2), B(2)));
+    assert_eq!(
+        world.query_all::<&A>().include::<&B>().single().err(),
+        Some(SingleError::MultipleMatches)
+    );
+    assert_eq!(world.query_all::<&A>().first(), Some(&A(0)));
+}
@@ -0,0 +1,80 @@
+mod common;
+
+use common::*;
+use sparsey::entity::Entity;
+use sparsey::World;
+
+#[test]
+fn test_iter_chunks_sparse() {
+    let mut world = World::builder()
+        .register::<A>()
+        .register::<B>()
+        .register::<C>()
+        .build();
+
+    let mut entities = (0..3)
+        .map(|i| world.create((A(i), B(i))))
+        .collect::<Vec<_>>();
+
+    // Offsets the dense indexes of B from the ones of A.
+    world.create((B(100),));
+    entities.extend((3..5).map(|i| world.create((A(i), B(i)))));
+    world.insert(entities[2], (C(2),));
+
+    let mut query = world.query_all::<(Entity, &mut A, &B)>();
+    let mut iter = query.iter_chunks();
+    assert!(iter.is_sparse());
+
+    let (chunk_entities, (slice_entities, a, b)) = iter.next().unwrap();
+    assert_eq!(chunk_entities, &entities[..3]);
+    assert_eq!(slice_entities, &entities[..3]);
+    assert_eq!(a, [A(0), A(1), A(2)]);
+    assert_eq!(b, [B(0), B(1), B(2)]);
+    a[0].0 = 10;
+
+    let (chunk_entities, (_, a, b)) = iter.next().unwrap();
+    assert_eq!(chunk_entities, &entities[3..]);
+    assert_eq!(a, [A(3), A(4)]);
+    assert_eq!(b, [B(3), B(4)]);
+
+    assert!(iter.next().is_none());
+    drop(query);
+
+    // Unmatched entities split runs.
+    let mut query = world.query_all::<&A>().exclude::<&C>();
+    let chunks = query
+        .iter_chunks()
+        .map(|(_, a)| a.iter().map(|a| a.0).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    assert_eq!(chunks, [vec![10, 1], vec![3, 4]]);
+}
+
+#[test]
+fn test_iter_chunks_dense() {
+    let mut world = World::builder()
+        .add_group::<(A, B)>()
+        .add_group::<(A, B, C)>()
+        .build();
+
+    let e0 = world.create((A(0), B(0), C(0)));
+    let e1 = world.create((A(1), B(1)));
+    let e2 = world.create((A(2), B(2)));
+
+    let mut query = world.query_all::<(&A, &B)>().exclude::<&C>();
+    let mut iter = query.iter_chunks();
+    assert!(iter.is_dense());
+
+    let (entities, (a, b)) = iter.next().unwrap();
+    assert_eq!(entities, [e1, e2]);
+    assert_eq!(a, [A(1), A(2)]);
+    assert_eq!(b, [B(1), B(2)]);
+    assert!(iter.next().is_none());
+
+    let (a, _) = query.slice().unwrap();
+    assert_eq!(a, [A(1), A(2)]);
+    drop(query);
+
+    let mut query = world.query_all::<(Entity, &A, &B, &C)>();
+    assert_eq!(query.iter_chunks().map(|(e, _)| e.len()).sum::<usize>(), 1);
+    assert_eq!(query.slice().unwrap().0, [e0]);
+}
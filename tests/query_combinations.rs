@@ -0,0 +1,74 @@
+mod common;
+
+use common::*;
+use sparsey::entity::Entity;
+use sparsey::World;
+
+#[test]
+fn test_iter_combinations() {
+    let mut world = World::builder()
+        .add_group::<(A, B)>()
+        .register::<C>()
+        .build();
+
+    let e0 = world.create((A(0), B(0)));
+    let e1 = world.create((A(1), B(1), C(1)));
+    let e2 = world.create((A(2), B(2), C(2)));
+    let e3 = world.create((A(3), C(3)));
+
+    let mut query = world.query_all::<(Entity, &A, &B)>();
+    let mut pairs = query
+        .iter_combinations::<2>()
+        .map(|[(x, _, _), (y, _, _)]| (x, y))
+        .collect::<Vec<_>>();
+    pairs.sort();
+    assert_eq!(pairs, [(e0, e1), (e0, e2), (e1, e2)]);
+    assert_eq!(query.iter_combinations::<3>().count(), 1);
+    assert_eq!(query.iter_combinations::<4>().count(), 0);
+    assert_eq!(query.iter_combinations::<0>().count(), 1);
+    drop(query);
+
+    let mut query = world.query_all::<&A>().include::<&C>();
+    let sums = query
+        .iter_combinations::<2>()
+        .map(|[x, y]| x.0 + y.0)
+        .collect::<Vec<_>>();
+    assert_eq!(sums.len(), 3);
+    assert_eq!(sums.iter().sum::<u32>(), 2 * (1 + 2 + 3));
+    drop(query);
+
+    let mut query = world.query_all::<(Entity, &C)>().exclude::<&B>();
+    assert_eq!(query.iter_combinations::<2>().next(), None);
+    let [(entity, c)] = query.iter_combinations::<1>().next().unwrap();
+    assert_eq!((entity, c), (e3, &C(3)));
+}
+
+#[test]
+fn test_iter_combinations_mut() {
+    let mut world = World::builder().register::<A>().register::<B>().build();
+
+    for i in 0..4 {
+        world.create((A(i), B(0)));
+    }
+
+    let mut query = world.query_all::<(&A, &mut B)>();
+    let mut iter = query.iter_combinations_mut::<2>();
+    let mut count = 0;
+
+    while let Some([(a0, b0), (a1, b1)]) = iter.fetch_next() {
+        b0.0 += a1.0;
+        b1.0 += a0.0;
+        count += 1;
+    }
+
+    assert_eq!(count, 6);
+    drop(query);
+
+    let mut b = world
+        .query_all::<(&A, &B)>()
+        .iter()
+        .map(|(a, b)| (a.0, b.0))
+        .collect::<Vec<_>>();
+    b.sort_unstable();
+    assert_eq!(b, [(0, 6), (1, 5), (2, 4), (3, 3)]);
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use common::*;
+use sparsey::World;
+
+#[test]
+fn test_iter_double_ended() {
+    let mut world = World::builder()
+        .add_group::<(A, B)>()
+        .register::<C>()
+        .build();
+
+    for i in 0..4 {
+        world.create((A(i), B(i)));
+    }
+
+    world.create((A(4), C(4)));
+
+    let mut query = world.query_all::<(&A, &B)>();
+    let mut iter = query.iter();
+    assert!(iter.is_dense());
+    let (first, _) = iter.next().unwrap();
+    let (last, _) = iter.next_back().unwrap();
+    assert_eq!(iter.size_hint(), (2, Some(2)));
+    let rest = iter.rev().map(|(a, _)| a.0).collect::<Vec<_>>();
+    assert_eq!(rest.len(), 2);
+    assert!(!rest.contains(&first.0) && !rest.contains(&last.0));
+    drop(query);
+
+    let mut query = world.query_all::<&A>().exclude::<&C>();
+    let forward = query.iter().map(|a| a.0).collect::<Vec<_>>();
+    let mut backward = query.iter().rev().map(|a| a.0).collect::<Vec<_>>();
+    backward.reverse();
+    assert_eq!(forward, backward);
+    assert_eq!(forward.len(), 4);
+}
+
+#[test]
+fn test_sparse_iter_double_ended() {
+    let mut world = World::builder().register::<A>().register::<B>().build();
+
+    let entities = (0..5).map(|i| world.create((A(i),))).collect::<Vec<_>>();
+
+    world.insert(entities[1], (B(1),));
+    world.insert(entities[3], (B(3),));
+
+    let mut query = world.query_all::<&A>().exclude::<&B>();
+    let mut iter = query.iter();
+    assert!(iter.is_sparse());
+    assert_eq!(iter.size_hint(), (0, Some(5)));
+    assert_eq!(iter.next_back(), Some(&A(4)));
+    assert_eq!(iter.next(), Some(&A(0)));
+    assert_eq!(iter.next_back(), Some(&A(2)));
+    assert_eq!(iter.next_back(), None);
+    assert_eq!(iter.next(), None);
+    drop(query);
+
+    let mut query = world.query_all::<&mut A>().include::<&B>();
+    for (i, a) in query.iter().rev().enumerate() {
+        a.0 = i as u32;
+    }
+    drop(query);
+
+    let mut query = world.query_all::<&A>().include::<&B>();
+    assert_eq!(query.iter().collect::<Vec<_>>(), [&A(1), &A(0)]);
+    assert_eq!(
+        query.iter_sorted_by_key(|a| a.0).rev().collect::<Vec<_>>(),
+        [&A(1), &A(0)]
+    );
+}
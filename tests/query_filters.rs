@@ -0,0 +1,172 @@
+mod common;
+
+use common::*;
+use sparsey::entity::Entity;
+use sparsey::query::{And, AnyOf, DenseRejection, Not, Or, QueryPlan};
+use sparsey::World;
+
+#[test]
+fn test_query_filters() {
+    let mut world = World::builder()
+        .add_group::<(A, B)>()
+        .register::<C>()
+        .register::<D>()
+        .register::<E>()
+        .build();
+
+    let e0 = world.create((A(0), B(0)));
+    let e1 = world.create((A(1), B(1), C(1)));
+    let e2 = world.create((A(2), D(2)));
+    let e3 = world.create((A(3), C(3), D(3), E(3)));
+    let e4 = world.create((E(4),));
+
+    let sorted = |mut entities: Vec<Entity>| {
+        entities.sort();
+        entities
+    };
+
+    let mut query = world.query_all::<Entity>().include::<(&A, Or<(&C, &D)>)>();
+    assert_eq!(sorted(query.iter().collect()), [e1, e2, e3]);
+
+    let mut query = world
+        .query_all::<Entity>()
+        .include::<Or<(And<(&A, &B)>, &E)>>();
+    assert_eq!(sorted(query.iter().collect()), [e0, e1, e3, e4]);
+
+    let mut query = world.query_all::<Entity>().include::<Not<(&C, &D)>>();
+    assert_eq!(sorted(query.iter().collect()), [e0, e1, e2, e4]);
+
+    let mut query = world
+        .query_all::<Entity>()
+        .include::<&A>()
+        .exclude::<Or<(&B, Not<(&E,)>)>>();
+    assert_eq!(sorted(query.iter().collect()), [e3]);
+
+    // Filters keep grouped queries from iterating densely.
+    let query = world.query_all::<(&A, &B)>().include::<Or<(&C, &D)>>();
+    assert!(matches!(
+        query.explain(),
+        QueryPlan::Sparse {
+            rejection: DenseRejection::SparseFilter,
+            ..
+        },
+    ));
+    drop(query);
+
+    // `And` reuses the grouping information of its parts.
+    let mut query = world.query_all::<Entity>().include::<And<(&A, &B)>>();
+    assert!(query.explain().is_dense());
+    assert_eq!(sorted(query.iter().collect()), [e0, e1]);
+    drop(query);
+
+    // `C` is not grouped, so `Not<&C>` is evaluated per entity.
+    let mut query = world.query_all::<(Entity, &A, &B)>().include::<Not<&C>>();
+    assert!(matches!(
+        query.explain(),
+        QueryPlan::Sparse {
+            rejection: DenseRejection::NoGroup,
+            ..
+        },
+    ));
+    assert_eq!(query.iter().map(|(e, ..)| e).collect::<Vec<_>>(), [e0]);
+    drop(query);
+
+    let mut query = world.query_all::<(Entity, &A, AnyOf<(&C, &D)>)>();
+    let mut items = query.iter().collect::<Vec<_>>();
+    items.sort_by_key(|(entity, ..)| *entity);
+    assert_eq!(
+        items,
+        [
+            (e1, &A(1), (Some(&C(1)), None)),
+            (e2, &A(2), (None, Some(&D(2)))),
+            (e3, &A(3), (Some(&C(3)), Some(&D(3)))),
+        ],
+    );
+    drop(query);
+
+    assert!(world.query_one::<Or<(&C, &D)>>().contains(e2));
+    assert!(!world.query_one::<Or<(&C, &D)>>().contains(e0));
+}
+
+#[test]
+fn test_query_filters_group_boundary() {
+    let mut world = World::builder()
+        .add_group::<(A, B)>()
+        .add_group::<(A, B, C)>()
+        .register::<D>()
+        .build();
+
+    let e0 = world.create((A(0), B(0)));
+    let e1 = world.create((A(1), B(1), C(1)));
+    let e2 = world.create((A(2), B(2), D(2)));
+    let e3 = world.create((A(3), C(3)));
+
+    let sorted = |mut entities: Vec<Entity>| {
+        entities.sort();
+        entities
+    };
+
+    // `Not` at a group boundary is planned like an "exclude" filter.
+    let mut query = world.query_all::<(Entity, &A, &B)>().include::<Not<&C>>();
+    assert!(query.explain().is_dense());
+    assert_eq!(sorted(query.iter().map(|(e, ..)| e).collect()), [e0, e2]);
+    drop(query);
+
+    let mut query = world.query_all::<(Entity, &A, &B, Not<&C>)>();
+    assert!(query.explain().is_dense());
+    assert_eq!(sorted(query.iter().map(|(e, ..)| e).collect()), [e0, e2]);
+    drop(query);
+
+    let mut query = world
+        .query_all::<Entity>()
+        .include::<And<(&A, &B, Not<&C>)>>();
+    assert!(query.explain().is_dense());
+    assert_eq!(sorted(query.iter().collect()), [e0, e2]);
+    drop(query);
+
+    let mut query = world.query_all::<Entity>().include::<And<(&A, &B, &C)>>();
+    assert!(query.explain().is_dense());
+    assert_eq!(sorted(query.iter().collect()), [e1]);
+    drop(query);
+
+    // `Not` away from a group boundary is evaluated per entity.
+    let mut query = world.query_all::<(Entity, &A)>().include::<Not<&C>>();
+    assert!(matches!(
+        query.explain(),
+        QueryPlan::Sparse {
+            rejection: DenseRejection::ExcludeNotAtGroupBoundary,
+            ..
+        },
+    ));
+    assert_eq!(sorted(query.iter().map(|(e, ..)| e).collect()), [e0, e2]);
+    drop(query);
+
+    // Two exclusions can't be bounded by one group.
+    let mut query = world
+        .query_all::<(Entity, &A, &B)>()
+        .include::<Not<&C>>()
+        .exclude::<&D>();
+    assert!(query.explain().is_sparse());
+    assert_eq!(query.iter().map(|(e, ..)| e).collect::<Vec<_>>(), [e0]);
+    drop(query);
+
+    // Negated exclusions are evaluated per entity.
+    let mut query = world.query_all::<(Entity, &A, &B)>().exclude::<Not<&C>>();
+    assert!(query.explain().is_sparse());
+    assert_eq!(query.iter().map(|(e, ..)| e).collect::<Vec<_>>(), [e1]);
+    drop(query);
+
+    let mut query = world.query_all::<(Entity, &A, &B, Not<Not<&C>>)>();
+    assert!(query.explain().is_sparse());
+    assert_eq!(query.iter().map(|(e, ..)| e).collect::<Vec<_>>(), [e1]);
+    drop(query);
+
+    let mut query = world.query_all::<Entity>().include::<Not<&C>>();
+    assert!(query.explain().is_sparse());
+    assert_eq!(sorted(query.iter().collect()), [e0, e2]);
+    drop(query);
+
+    let mut query = world.query_all::<Entity>().include::<(&A, Not<&B>)>();
+    assert!(query.explain().is_sparse());
+    assert_eq!(query.iter().collect::<Vec<_>>(), [e3]);
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use common::*;
+use sparsey::entity::Entity;
+use sparsey::query::GetManyError;
+use sparsey::World;
+
+#[test]
+fn test_get_many_mut() {
+    let mut world = World::builder().register::<A>().register::<B>().build();
+
+    let e0 = world.create((A(10), B(1)));
+    let e1 = world.create((A(20), B(2)));
+    let e2 = world.create((A(30),));
+
+    let mut query = world.query_one::<(&mut A, &B)>();
+    let [(a0, b0), (a1, b1)] = query.get_many_mut([e0, e1]).unwrap();
+    a0.0 -= b1.0;
+    a1.0 -= b0.0;
+    assert_eq!((a0.0, a1.0), (8, 19));
+
+    assert!(query.get_many_mut([e0, e2]).is_none());
+    assert_eq!(
+        query.get_many_mut::<0>([]).map(|items| items.len()),
+        Some(0)
+    );
+    drop(query);
+
+    let mut query = world.query_one::<&A>().exclude::<&B>();
+    let [a2] = query.get_many_mut([e2]).unwrap();
+    assert_eq!(*a2, A(30));
+}
+
+#[test]
+fn test_try_get_many_mut() {
+    let mut world = World::builder().register::<A>().register::<B>().build();
+
+    let e0 = world.create((A(0), B(0)));
+    let e1 = world.create((A(1),));
+    world.destroy(e1);
+    let e2 = world.create((A(2),));
+
+    let mut query = world.query_one::<&mut A>();
+
+    assert_eq!(
+        query.try_get_many_mut([e0, e2, e0]).err(),
+        Some(GetManyError::Duplicate(e0)),
+    );
+    assert_eq!(
+        query.try_get_many_mut([e2, e1]).err(),
+        Some(GetManyError::Aliased(e2, e1)),
+    );
+    assert_eq!(
+        query.try_get_many_mut([e0, e1]).err(),
+        Some(GetManyError::NoMatch(e1)),
+    );
+
+    let items = query.try_get_many_mut([e2, e0]).unwrap();
+    assert_eq!(items.map(|a| a.0), [2, 0]);
+    drop(query);
+
+    let mut query = world.query_one::<(Entity, &A)>().include::<&B>();
+    assert_eq!(
+        query.try_get_many_mut([e2]).err(),
+        Some(GetManyError::NoMatch(e2)),
+    );
+}
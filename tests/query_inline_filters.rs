@@ -0,0 +1,72 @@
+mod common;
+
+use common::*;
+use sparsey::entity::Entity;
+use sparsey::query::{Has, With, Without};
+use sparsey::World;
+
+#[test]
+fn test_query_inline_filters() {
+    let mut world = World::builder()
+        .add_group::<(A, B)>()
+        .register::<C>()
+        .build();
+
+    let e0 = world.create((A(0), B(0)));
+    let e1 = world.create((A(1), B(1), C(1)));
+    let e2 = world.create((A(2), C(2)));
+
+    let sorted = |mut entities: Vec<Entity>| {
+        entities.sort();
+        entities
+    };
+
+    // `With` keeps the query dense.
+    let mut query = world.query_all::<(Entity, &A, With<B>)>();
+    assert!(query.explain().is_dense());
+    assert_eq!(
+        sorted(query.iter().map(|(entity, _, ())| entity).collect()),
+        [e0, e1]
+    );
+    drop(query);
+
+    let mut query = world.query_all::<(Entity, &A, Without<C>)>();
+    assert_eq!(
+        query
+            .iter()
+            .map(|(entity, _, ())| entity)
+            .collect::<Vec<_>>(),
+        [e0]
+    );
+    drop(query);
+
+    // `Without` is always evaluated per entity.
+    assert!(world
+        .query_all::<(&A, &B, Without<C>)>()
+        .explain()
+        .is_sparse());
+
+    let mut query = world.query_all::<(Entity, Has<C>)>().include::<&A>();
+    assert_eq!(
+        sorted(query.iter().map(|(entity, _)| entity).collect()),
+        [e0, e1, e2]
+    );
+    assert!(query.iter().all(|(entity, has_c)| has_c == (entity != e0)));
+    drop(query);
+
+    // `Has` doesn't prevent dense iteration.
+    let mut query = world.query_all::<(&A, &B, Has<C>)>();
+    assert!(query.explain().is_dense());
+    let mut has_c = query
+        .iter()
+        .map(|(a, _, has_c)| (a.0, has_c))
+        .collect::<Vec<_>>();
+    has_c.sort_unstable();
+    assert_eq!(has_c, [(0, false), (1, true)]);
+    drop(query);
+
+    let mut query = world.query_one::<(&A, With<B>, Without<C>, Has<C>)>();
+    assert!(query.get(e0).is_some());
+    assert!(query.get(e1).is_none());
+    assert!(query.get(e2).is_none());
+}
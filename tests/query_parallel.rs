@@ -0,0 +1,90 @@
+#![cfg(feature = "parallel")]
+
+mod common;
+
+use common::*;
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
+use sparsey::query::ParIter;
+use sparsey::World;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[test]
+fn test_par_iter_len_bounds() {
+    let mut world = World::builder()
+        .add_group::<(A, B)>()
+        .register::<C>()
+        .build();
+
+    for i in 0..100 {
+        world.create((A(i), B(i)));
+        world.create((A(i), C(i)));
+    }
+
+    let mut query = world.query_all::<&mut A>().include::<&B>();
+    let iter = query.par_iter().with_min_len(16).with_max_len(32);
+    assert!(iter.is_dense());
+    iter.for_each(|a| a.0 += 1);
+    drop(query);
+
+    let mut query = world.query_all::<&A>().include::<&C>();
+    let iter = query.par_iter().with_min_len(1000);
+    assert!(iter.is_sparse());
+    assert_eq!(iter.map(|a| u64::from(a.0)).sum::<u64>(), 4950);
+    drop(query);
+
+    let mut query = world.query_all::<(&A, &B)>();
+    let (_, b) = query.slice().unwrap();
+    let expected = b.iter().map(|b| b.0 + 1).collect::<Vec<_>>();
+
+    let ParIter::Dense(iter) = query.par_iter().with_max_len(8) else {
+        panic!("expected a dense iterator");
+    };
+    assert_eq!(iter.len(), 100);
+    assert!(iter
+        .zip(expected.par_iter())
+        .all(|((a, _), &expected)| a.0 == expected));
+
+    // The bounds control how many tasks the items are split into.
+    let mut init_calls = |min_len: usize, max_len: usize| {
+        let calls = AtomicUsize::new(0);
+
+        query
+            .par_iter()
+            .with_min_len(min_len)
+            .with_max_len(max_len)
+            .for_each_init(|| calls.fetch_add(1, Ordering::Relaxed), |_, _| ());
+
+        calls.into_inner()
+    };
+
+    assert_eq!(init_calls(100, usize::MAX), 1);
+    assert_eq!(init_calls(1, 1), 100);
+}
+
+#[test]
+fn test_par_for_each_init() {
+    let mut world = World::builder().register::<A>().register::<B>().build();
+
+    for i in 0..64 {
+        world.create((A(i), B(i)));
+    }
+
+    let inits = AtomicUsize::new(0);
+    let mut query = world.query_all::<(&mut A, &B)>();
+    query.par_for_each_init(
+        || {
+            inits.fetch_add(1, Ordering::Relaxed);
+            Vec::new()
+        },
+        |scratch: &mut Vec<u32>, (a, b)| {
+            scratch.clear();
+            scratch.extend([a.0, b.0]);
+            a.0 = scratch.iter().sum();
+        },
+    );
+    drop(query);
+
+    assert!(inits.load(Ordering::Relaxed) >= 1);
+    let mut query = world.query_all::<(&A, &B)>();
+    query.for_each(|(a, b)| assert_eq!(a.0, 2 * b.0));
+}
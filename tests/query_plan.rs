@@ -0,0 +1,96 @@
+mod common;
+
+use common::*;
+use sparsey::component::ComponentData;
+use sparsey::query::{DenseRejection, QueryPlan, SparseDriver};
+use sparsey::World;
+
+#[test]
+fn test_query_plan() {
+    let mut world = World::builder()
+        .add_group::<(A, B)>()
+        .add_group::<(A, B, C)>()
+        .register::<D>()
+        .register::<E>()
+        .build();
+
+    world.create((A(0), B(0), C(0)));
+    world.create((A(1), B(1)));
+    world.create((A(2), D(2)));
+
+    assert_eq!(
+        world.query_all::<(&A, &B)>().explain(),
+        QueryPlan::Dense { range: 0..2 },
+    );
+    assert_eq!(
+        world.query_all::<(&A, &B)>().exclude::<&C>().explain(),
+        QueryPlan::Dense { range: 1..2 },
+    );
+    assert_eq!(
+        world.query_all::<&E>().explain(),
+        QueryPlan::Dense { range: 0..0 },
+    );
+
+    let sparse = |driver, driver_component, len, rejection| QueryPlan::Sparse {
+        driver,
+        driver_component,
+        len,
+        rejection,
+    };
+
+    assert_eq!(
+        world.query_all::<(&A, &D)>().explain(),
+        sparse(
+            SparseDriver::Get,
+            Some(ComponentData::new::<D>()),
+            1,
+            DenseRejection::NoGroup
+        ),
+    );
+    assert_eq!(
+        world.query_all::<(&A, &C)>().explain(),
+        sparse(
+            SparseDriver::Get,
+            Some(ComponentData::new::<C>()),
+            1,
+            DenseRejection::MismatchedGroup
+        ),
+    );
+    assert_eq!(
+        world.query_all::<&A>().exclude::<&B>().explain(),
+        sparse(
+            SparseDriver::Get,
+            Some(ComponentData::new::<A>()),
+            3,
+            DenseRejection::ExcludeNotAtGroupBoundary
+        ),
+    );
+    assert_eq!(
+        world
+            .query_all::<Option<&A>>()
+            .include::<(&C, &D)>()
+            .explain(),
+        sparse(
+            SparseDriver::Include,
+            Some(ComponentData::new::<C>()),
+            1,
+            DenseRejection::NoGroup
+        ),
+    );
+    assert_eq!(
+        world.query_all::<Option<&A>>().explain(),
+        sparse(SparseDriver::None, None, 0, DenseRejection::Unconstrained),
+    );
+
+    world.batch(|world| {
+        assert_eq!(
+            world.query_all::<(&A, &B)>().explain(),
+            sparse(
+                SparseDriver::Get,
+                Some(ComponentData::new::<B>()),
+                2,
+                DenseRejection::GroupingDeferred
+            ),
+        );
+    });
+}
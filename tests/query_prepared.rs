@@ -0,0 +1,114 @@
+mod common;
+
+use common::*;
+use sparsey::entity::Entity;
+use sparsey::query::PreparedQuery;
+use sparsey::World;
+
+#[test]
+fn test_prepared_query() {
+    let mut world = World::builder()
+        .add_group::<(A, B)>()
+        .add_group::<(A, B, C)>()
+        .build();
+
+    let e0 = world.create((A(0), B(0)));
+    let e1 = world.create((A(1), B(1), C(1)));
+
+    let mut query = world
+        .query_all::<(Entity, &mut A, &B)>()
+        .exclude::<&C>()
+        .prepare();
+
+    assert!(query.is_prepared_for(&world));
+    assert!(query.run(&world).explain().is_dense());
+    assert_eq!(
+        query
+            .run(&world)
+            .iter()
+            .map(|(e, _, _)| e)
+            .collect::<Vec<_>>(),
+        [e0]
+    );
+
+    // Group lengths are read on every run.
+    let e2 = world.create((A(2), B(2)));
+    query.run(&world).for_each(|(_, a, b)| a.0 += b.0);
+    let mut a = world.query_one::<&A>();
+    assert_eq!(a.get(e0).map(|a| a.0), Some(0));
+    assert_eq!(a.get(e1).map(|a| a.0), Some(1));
+    assert_eq!(a.get(e2).map(|a| a.0), Some(4));
+    drop(a);
+
+    // Changing the layout invalidates the cached lookups.
+    world.remove_group::<(A, B, C)>();
+    assert!(!query.is_prepared_for(&world));
+
+    let mut entities = query
+        .run(&world)
+        .iter()
+        .map(|(e, _, _)| e)
+        .collect::<Vec<_>>();
+    entities.sort();
+    assert_eq!(entities, [e0, e2]);
+    assert!(query.is_prepared_for(&world));
+
+    // Queries prepared on one world are refreshed for another.
+    let mut other = World::builder()
+        .register::<A>()
+        .register::<B>()
+        .register::<C>()
+        .build();
+    let e3 = other.create((A(3), B(3)));
+    assert!(!query.is_prepared_for(&other));
+    assert_eq!(
+        query
+            .run(&other)
+            .iter()
+            .map(|(e, _, _)| e)
+            .collect::<Vec<_>>(),
+        [e3]
+    );
+}
+
+#[test]
+fn test_prepared_query_new() {
+    let mut world = World::builder()
+        .add_group::<(A, B)>()
+        .register::<C>()
+        .build();
+
+    world.create((A(0), B(0)));
+    world.create((A(1), B(1), C(1)));
+    world.create((A(2), C(2)));
+
+    let mut query = PreparedQuery::<&A, &B>::new(&world);
+    assert_eq!(query.run(&world).slice().map(<[A]>::len), Some(2));
+
+    let mut query = PreparedQuery::<&A, (), &C>::new(&world);
+    assert!(query.run(&world).explain().is_sparse());
+    assert_eq!(query.run(&world).iter().count(), 1);
+}
+
+#[test]
+fn test_prepared_query_default_worlds() {
+    let mut world = World::default();
+    world.register::<A>();
+    world.register::<B>();
+    world.create((A(0), B(1)));
+
+    let mut other = World::default();
+    other.register::<B>();
+    other.register::<A>();
+    other.create((A(2), B(3)));
+
+    let mut query = PreparedQuery::<&B>::new(&world);
+    assert!(query.is_prepared_for(&world));
+    assert!(!query.is_prepared_for(&other));
+    assert_eq!(query.run(&other).iter().collect::<Vec<_>>(), [&B(3)]);
+
+    // Adding storages invalidates prepared queries.
+    let query = PreparedQuery::<&A>::new(&world);
+    world.register::<C>();
+    assert!(!query.is_prepared_for(&world));
+}
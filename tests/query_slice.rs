@@ -0,0 +1,24 @@
+mod common;
+
+use common::*;
+use sparsey::entity::Entity;
+use sparsey::World;
+
+#[test]
+fn test_slice_exclude() {
+    let mut world = World::builder()
+        .add_group::<(A, B)>()
+        .add_group::<(A, B, C)>()
+        .build();
+
+    world.create((A(0), B(0), C(0)));
+    let e1 = world.create((A(1), B(1)));
+    let e2 = world.create((A(2), B(2)));
+
+    // The excluded range starts after the components of the child group.
+    let mut query = world.query_all::<(Entity, &A, &mut B)>().exclude::<&C>();
+    let (entities, a, b) = query.slice().unwrap();
+    assert_eq!(entities, [e1, e2]);
+    assert_eq!(a, [A(1), A(2)]);
+    assert_eq!(b, [B(1), B(2)]);
+}
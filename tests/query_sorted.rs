@@ -0,0 +1,90 @@
+mod common;
+
+use common::*;
+use sparsey::entity::Entity;
+use sparsey::query::SortedIter;
+use sparsey::World;
+
+#[test]
+fn test_iter_sorted_by_entity() {
+    let mut world = World::builder()
+        .add_group::<(A, B)>()
+        .register::<C>()
+        .build();
+
+    let entities = (0..5)
+        .map(|i| world.create((A(i), C(i))))
+        .collect::<Vec<_>>();
+
+    // Grouping reorders the components of A.
+    for &i in &[3, 1, 4] {
+        world.insert(entities[i], (B(i as u32),));
+    }
+
+    let mut query = world.query_all::<(Entity, &A)>();
+    let mut iter = query.iter_sorted_by_entity();
+    assert!(iter.is_lookup());
+    assert_eq!(iter.by_ref().map(|(e, _)| e).collect::<Vec<_>>(), entities);
+    drop(query);
+
+    let mut query = world.query_all::<(Entity, &A, &C)>().exclude::<&B>();
+    assert_eq!(
+        query
+            .iter_sorted_by_entity()
+            .map(|(e, _, _)| e)
+            .collect::<Vec<_>>(),
+        [entities[0], entities[2]]
+    );
+    drop(query);
+
+    // C was never reordered, so it is iterated directly.
+    let mut query = world.query_all::<(Entity, &C)>();
+    let iter = query.iter_sorted_by_entity();
+    assert!(iter.is_direct());
+    assert_eq!(iter.map(|(e, _)| e).collect::<Vec<_>>(), entities);
+}
+
+#[test]
+fn test_iter_sorted_by_key() {
+    let mut world = World::builder().register::<A>().register::<B>().build();
+
+    let e0 = world.create((A(2), B(0)));
+    let e1 = world.create((A(0), B(1)));
+    let e2 = world.create((A(2), B(2)));
+    let e3 = world.create((A(1),));
+
+    let mut query = world.query_all::<(Entity, &mut A)>().include::<&B>();
+    let mut calls = 0;
+    let iter = query.iter_sorted_by_key(|(_, a)| {
+        calls += 1;
+        a.0
+    });
+    assert!(iter.is_lookup());
+    assert_eq!(calls, 3);
+
+    let SortedIter::Lookup(lookup) = &iter else {
+        unreachable!();
+    };
+    assert_eq!(lookup.len(), 3);
+
+    let mut order = Vec::new();
+    for (entity, a) in iter {
+        a.0 += 10;
+        order.push(entity);
+    }
+    assert_eq!(order, [e1, e0, e2]);
+    drop(query);
+
+    let mut query = world.query_all::<(Entity, &A)>();
+    assert_eq!(
+        query
+            .iter_sorted_by_key(|(_, a)| a.0)
+            .map(|(e, _)| e)
+            .collect::<Vec<_>>(),
+        [e3, e1, e0, e2]
+    );
+
+    let iter = query.iter_sorted_by_key(|(entity, _)| *entity);
+    assert!(iter.is_direct());
+    assert_eq!(iter.count(), 4);
+}
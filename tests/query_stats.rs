@@ -0,0 +1,134 @@
+#![cfg(feature = "std")]
+
+mod common;
+
+use common::*;
+use sparsey::component::{ComponentData, GroupDescriptor};
+use sparsey::World;
+
+#[test]
+fn test_suggest_layout() {
+    let mut world = World::builder()
+        .register::<A>()
+        .register::<B>()
+        .register::<C>()
+        .register::<D>()
+        .register::<E>()
+        .build();
+
+    world.extend((0..10).map(|i| (A(i), B(i), C(i), D(i), E(i))));
+
+    // Not recorded, profiling is disabled.
+    world.for_each::<(&D, &E)>(|_| ());
+    assert!(world.query_stats().is_empty());
+
+    world.set_query_profiling(true);
+
+    for _ in 0..3 {
+        world.for_each::<(&A, &B)>(|_| ());
+        world
+            .query_all::<(&A, &mut B)>()
+            .exclude::<&C>()
+            .for_each(|_| ());
+    }
+
+    // Conflicts with (A, B) and visits fewer entities.
+    world.for_each::<(&B, &D)>(|_| ());
+
+    let stats = world.query_stats();
+    assert_eq!(stats.len(), 3);
+    assert_eq!(stats[0].runs, 3);
+    assert_eq!(stats[0].visited, 30);
+    assert_eq!(
+        stats[2].get,
+        [ComponentData::new::<B>(), ComponentData::new::<D>()]
+    );
+
+    let layout = world.suggest_layout();
+    world.set_layout(&layout);
+
+    let groups = world
+        .groups()
+        .map(|group| sorted(group.components()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(groups, [components::<(A, B)>(), components::<(A, B, C)>()]);
+    assert!(world
+        .query_all::<(&A, &B)>()
+        .exclude::<&C>()
+        .explain()
+        .is_dense());
+
+    world.clear_query_stats();
+    assert!(world.query_stats().is_empty());
+}
+
+#[test]
+fn test_suggest_layout_ties() {
+    let mut world = World::builder()
+        .register::<A>()
+        .register::<B>()
+        .register::<C>()
+        .build();
+
+    world.extend((0..10).map(|i| (A(i), B(i), C(i))));
+    world.set_query_profiling(true);
+
+    // Both queries visit the same number of entities and conflict.
+    world.for_each::<(&C, &B)>(|_| ());
+    world.for_each::<(&B, &A)>(|_| ());
+
+    let stats = world.query_stats();
+    assert_eq!(stats[0].get, names_sorted::<(A, B)>());
+    assert_eq!(stats[1].get, names_sorted::<(B, C)>());
+
+    let layout = world.suggest_layout();
+    world.set_layout(&layout);
+
+    let groups = world
+        .groups()
+        .map(|group| sorted(group.components()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(groups, [components::<(A, B)>()]);
+}
+
+fn components<G>() -> Vec<ComponentData>
+where
+    G: GroupDescriptor,
+{
+    sorted(G::COMPONENTS)
+}
+
+fn sorted(components: &[ComponentData]) -> Vec<ComponentData> {
+    let mut components = components.to_vec();
+    components.sort_unstable();
+    components
+}
+
+fn names_sorted<G>() -> Vec<ComponentData>
+where
+    G: GroupDescriptor,
+{
+    let mut components = G::COMPONENTS.to_vec();
+    components.sort_unstable_by_key(ComponentData::type_name);
+    components
+}
+
+#[test]
+fn test_sorted_iteration_records_one_run() {
+    let mut world = World::builder().register::<A>().build();
+    world.extend((0..4).map(|i| (A(i),)));
+    world.set_query_profiling(true);
+
+    // Already sorted, so the storage is iterated directly.
+    let mut query = world.query_all::<&A>();
+    assert!(query.iter_sorted_by_key(|a| a.0).is_direct());
+    assert!(query.iter_sorted_by_key(|a| u32::MAX - a.0).is_lookup());
+    drop(query);
+
+    let stats = world.query_stats();
+    assert_eq!(stats.len(), 1);
+    assert_eq!(stats[0].runs, 2);
+    assert_eq!(stats[0].visited, 8);
+}